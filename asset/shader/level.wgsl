@@ -32,6 +32,11 @@ var<storage, read> material_index: MaterialIndex;
 struct PushConstants {
     clock: u32,
     lightmap_material_id: u32,
+    // 0 = none, 1 = linear, 2 = exponential
+    fog_mode: u32,
+    fog_start: f32,
+    fog_density: f32,
+    fog_color: vec4<f32>,
 }
 
 var<push_constant> pc: PushConstants;
@@ -71,6 +76,20 @@ fn sample_material(material_id: u32, uv: vec2<f32>) -> vec4<f32> {
     return sampled * material_color;
 }
 
+fn apply_fog(color: vec4<f32>, distance: f32) -> vec4<f32> {
+    if (pc.fog_mode == 0u) {
+        return color;
+    }
+
+    let fog_distance = max(distance - pc.fog_start, 0.0);
+    var amount = 1.0 - exp(-pc.fog_density * fog_distance);
+    if (pc.fog_mode == 1u) {
+        amount = clamp(pc.fog_density * fog_distance, 0.0, 1.0);
+    }
+    let rgb = mix(color.rgb, pc.fog_color.rgb, amount * pc.fog_color.a);
+    return vec4<f32>(rgb, color.a);
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) diffuse_uv: vec2<f32>,
@@ -84,6 +103,7 @@ struct VertexOutput {
     @location(1) lightmap_uv: vec2<f32>,
     @location(2) world_position: vec3<f32>,
     @location(3) @interpolate(flat) material_ix: u32,
+    @location(4) view_position: vec3<f32>,
 }
 
 @vertex
@@ -97,6 +117,7 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     out.lightmap_uv = in.lightmap_uv;
     out.world_position = world_position.xyz;
     out.material_ix = in.material_ix;
+    out.view_position = view_position.xyz;
     return out;
 }
 
@@ -112,10 +133,12 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 
     let diffuse_color = sample_material(in.material_ix, in.diffuse_uv);
     let material = material_index.entries[in.material_ix];
+    // Distance from the eye, which through a portal includes the distance to the portal.
+    let distance = length(in.view_position);
     if (material.unlit != 0u) {
-        return diffuse_color;
+        return apply_fog(diffuse_color, distance);
     }
 
     let light = sample_material(pc.lightmap_material_id, in.lightmap_uv);
-    return diffuse_color * light;
+    return apply_fog(diffuse_color * light, distance);
 }
@@ -0,0 +1,36 @@
+// Group 0: Textures
+@group(0) @binding(0)
+var sky_sampler: sampler;
+@group(0) @binding(1)
+var sky: texture_cube<f32>;
+
+struct PushConstants {
+    inverse_view_projection: mat4x4<f32>,
+}
+
+var<push_constant> pc: PushConstants;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+}
+
+// One triangle covering the screen.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_ix: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((vertex_ix << 1u) & 2u), f32(vertex_ix & 2u));
+    let ndc = uv * 2.0 - 1.0;
+
+    out.clip_position = vec4<f32>(ndc, 0.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let near = pc.inverse_view_projection * vec4<f32>(in.ndc, 0.0, 1.0);
+    let far = pc.inverse_view_projection * vec4<f32>(in.ndc, 1.0, 1.0);
+    let direction = far.xyz / far.w - near.xyz / near.w;
+    return textureSample(sky, sky_sampler, normalize(direction));
+}
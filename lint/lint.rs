@@ -0,0 +1,714 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use url::Url;
+
+use halls::audio::TrackData;
+use halls::color::Color;
+use halls::gltf::GLTFMesh;
+use halls::graphics::pipeline::level::{texture_bucket_alloc, texture_bucket_fit, TEXTURE_BUCKETS};
+use halls::graphics::storage::{MaterialIndexStorageBufferData, MaterialTextureRef};
+use halls::level::fetch::{
+    fetch, FetchBatch, FetchBundle, FetchIntegrity, FetchLevelBudget, FetchLimits,
+};
+use halls::level::manifest::{
+    LevelManifest, LevelManifestEnvironment, LevelManifestLibraryLoadError, LevelManifestLoadError,
+    LevelManifestSky, LevelManifestSpawn, LevelManifestSurface, LevelManifestSurfaces,
+};
+use halls::level::portal::LevelPortalGeometry;
+use halls::level::sky::{sky_cubemap_size, sky_equirectangular_size};
+use halls::level::{Level, LevelLoadCancel, LevelLoadProgress};
+
+const STATIC_ANIMATION_SPEED: f32 = 0.0;
+const MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    pub check: &'static str,
+    pub message: String,
+}
+
+// One line for `err` and its sources, as the visit menu shows a failed load.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message = format!("{message}: {err}");
+        source = err.source();
+    }
+    return message;
+}
+
+// Files beneath `dir`, skipping dotfiles and subdirectories holding another level.
+fn files_walk(dir: &Path, root: bool, files: &mut Vec<PathBuf>) {
+    if !root && dir.join(MANIFEST_NAME).exists() {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            files_walk(&path, false, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+// A level's manifest and where its assets come from, loaded as `Level::load` does.
+struct LintSource {
+    base_url: Url,
+    manifest: LevelManifest,
+    bundle: Option<FetchBundle>,
+}
+
+impl LintSource {
+    fn load(url: &Url, limits: &FetchLimits) -> Result<Self, LevelManifestLoadError> {
+        if FetchBundle::is_bundle(url) {
+            let bundle = FetchBundle::load(url, limits, None, &LevelLoadProgress::default())
+                .map_err(LevelManifestLoadError::Fetch)?;
+            let manifest = LevelManifest::load_bundle(&bundle, false)?;
+            return Ok(Self {
+                base_url: bundle.base_url().clone(),
+                manifest,
+                bundle: Some(bundle),
+            });
+        }
+        let manifest = LevelManifest::load(url, limits, None, false)?;
+        return Ok(Self {
+            base_url: url.clone(),
+            manifest,
+            bundle: None,
+        });
+    }
+
+    fn surfaces_load(
+        &self,
+        limits: &FetchLimits,
+    ) -> Result<LevelManifestSurfaces, LevelManifestLibraryLoadError> {
+        return LevelManifestSurfaces::load(&self.manifest, &self.base_url, false, |library_url| {
+            return match &self.bundle {
+                Some(bundle) => Ok(bundle.get(library_url)?.to_vec()),
+                None => Ok(fetch(library_url, limits, None)?),
+            };
+        });
+    }
+
+    fn batch(&mut self, urls: Vec<Url>, limits: &FetchLimits) -> FetchBatch {
+        let integrity: FetchIntegrity = self
+            .manifest
+            .integrity_iter()
+            .filter_map(|(href, digest)| Some((self.base_url.join(href).ok()?, *digest)))
+            .collect();
+        return match self.bundle.take() {
+            Some(bundle) => FetchBatch::from_bundle(urls, &integrity, bundle),
+            None => FetchBatch::load(
+                urls,
+                &integrity,
+                limits,
+                None,
+                &FetchLevelBudget::default(),
+                &LevelLoadCancel::default(),
+                &LevelLoadProgress::default(),
+            ),
+        };
+    }
+}
+
+// Texture layers handed out so far by frame URL, mirroring the game's material upload.
+struct LintTextures {
+    refs: HashMap<String, Option<MaterialTextureRef>>,
+    next_free: [usize; TEXTURE_BUCKETS.len()],
+    // Buckets already reported full, as later images fail for the same reason.
+    full: [bool; TEXTURE_BUCKETS.len()],
+}
+
+pub struct Lint {
+    url: Url,
+    limits: FetchLimits,
+    findings: Vec<LintFinding>,
+}
+
+impl Lint {
+    pub fn run(url: Url, limits: FetchLimits) -> Vec<LintFinding> {
+        let mut lint = Self {
+            url,
+            limits,
+            findings: Vec::new(),
+        };
+        lint.level_lint();
+        return lint.findings;
+    }
+
+    fn error(&mut self, check: &'static str, message: String) {
+        self.findings.push(LintFinding {
+            severity: LintSeverity::Error,
+            check,
+            message,
+        });
+    }
+
+    fn warning(&mut self, check: &'static str, message: String) {
+        self.findings.push(LintFinding {
+            severity: LintSeverity::Warning,
+            check,
+            message,
+        });
+    }
+
+    // Looks up `href` in `batch`, reporting why it is unavailable under `check`.
+    fn asset_get<'a>(
+        &mut self,
+        check: &'static str,
+        base_url: &Url,
+        batch: &'a FetchBatch,
+        href: &str,
+    ) -> Option<(Url, &'a [u8])> {
+        let Ok(url) = base_url.join(href) else {
+            self.error(check, format!("invalid URL \"{href}\""));
+            return None;
+        };
+        return match batch.get(&url) {
+            Ok(data) => Some((url, data)),
+            Err(err) => {
+                self.error(
+                    check,
+                    format!("failed to fetch {url}: {}", error_chain(&err)),
+                );
+                None
+            }
+        };
+    }
+
+    fn mesh_get(
+        &mut self,
+        check: &'static str,
+        base_url: &Url,
+        batch: &FetchBatch,
+        href: &str,
+    ) -> Option<GLTFMesh> {
+        let (url, data) = self.asset_get(check, base_url, batch, href)?;
+        return match GLTFMesh::from_bytes(data) {
+            Ok(mesh) => Some(mesh),
+            Err(err) => {
+                self.error(
+                    check,
+                    format!("failed to parse mesh {url}: {}", error_chain(&err)),
+                );
+                None
+            }
+        };
+    }
+
+    fn level_lint(&mut self) {
+        let url = self.url.clone();
+        let mut source = match LintSource::load(&url, &self.limits) {
+            Ok(source) => source,
+            Err(err) => {
+                self.error("manifest", error_chain(&err));
+                return;
+            }
+        };
+        for path in source.manifest.unknown_fields() {
+            self.warning(
+                "unknown-field",
+                format!("manifest field \"{path}\" is unknown and ignored"),
+            );
+        }
+        let base_url = source.base_url.clone();
+        // The rest of the level is still checked against its own surfaces.
+        let surfaces = match source.surfaces_load(&self.limits) {
+            Ok(surfaces) => surfaces,
+            Err(err) => {
+                self.error("surface-library", error_chain(&err));
+                LevelManifestSurfaces::local(&source.manifest, &base_url)
+            }
+        };
+        for path in surfaces.unknown_fields() {
+            self.warning(
+                "unknown-field",
+                format!("surface library field \"{path}\" is unknown and ignored"),
+            );
+        }
+        let mut asset_urls: Vec<Url> = source
+            .manifest
+            .asset_hrefs()
+            .into_iter()
+            .filter_map(|href| base_url.join(href).ok())
+            .collect();
+        asset_urls.extend(surfaces.asset_urls());
+        let batch = source.batch(asset_urls.clone(), &self.limits);
+        let manifest = &source.manifest;
+
+        if let Some(mesh) = self.mesh_get("mesh", &base_url, &batch, manifest.level().mesh()) {
+            self.surfaces_lint(&base_url, &batch, manifest, &surfaces, &mesh);
+            let lightmap_uvs = mesh.vertices().next().map(|vertex| vertex.lightmap_uv);
+            if manifest.level().lightmap().is_some() && lightmap_uvs.is_some_and(|uv| uv.is_none())
+            {
+                self.warning(
+                    "lightmap-uv",
+                    "level has a lightmap but its mesh has no second UV set, so the lightmap \
+                     is sampled at a single point"
+                        .to_string(),
+                );
+            }
+        }
+
+        if let Some(collider) = manifest.level().collider() {
+            self.mesh_get("collider", &base_url, &batch, collider.mesh());
+        }
+
+        if let Some(sky) = manifest
+            .level()
+            .environment()
+            .and_then(LevelManifestEnvironment::sky)
+        {
+            self.sky_lint(&base_url, &batch, sky);
+        }
+
+        if let Some(track_href) = manifest.level().track() {
+            if let Some((track_url, data)) = self.asset_get("track", &base_url, &batch, track_href)
+            {
+                if let Err(err) = TrackData::new(data, false) {
+                    self.error(
+                        "track",
+                        format!("failed to decode audio {track_url}: {}", error_chain(&err)),
+                    );
+                }
+            }
+        }
+
+        // The game shows the level without a thumbnail it cannot load.
+        if let Some(thumbnail_href) = manifest.meta().thumbnail() {
+            match Level::thumbnail_load(&base_url, &batch, thumbnail_href) {
+                Ok(_) => {}
+                Err(err) => self.warning("thumbnail", error_chain(&err)),
+            }
+        }
+
+        let mut portals: Vec<_> = manifest.portal_iter().collect();
+        portals.sort_by_key(|(name, _)| name.as_str());
+        for (name, portal) in portals {
+            let owner = format!("portal \"{name}\"");
+            let geometry = self.geometry_get("portal", &owner, &base_url, &batch, portal.mesh());
+            self.link_lint(name, portal.link_href(), geometry.as_ref());
+        }
+
+        asset_urls.extend(surfaces.library_urls().iter().cloned());
+        self.unreferenced_lint(&asset_urls);
+    }
+
+    fn surfaces_lint(
+        &mut self,
+        base_url: &Url,
+        batch: &FetchBatch,
+        manifest: &LevelManifest,
+        level_surfaces: &LevelManifestSurfaces,
+        mesh: &GLTFMesh,
+    ) {
+        let mut surfaces = Vec::with_capacity(mesh.materials().len());
+        let mut used = HashSet::new();
+        for (ix, material) in mesh.materials().iter().enumerate() {
+            let surface = match material {
+                Some(name) => {
+                    let surface = level_surfaces.entry(name);
+                    if surface.is_none() {
+                        self.warning(
+                            "unmapped-material",
+                            format!(
+                                "mesh material \"{name}\" has no level.surface entry or library \
+                                 surface, so its geometry is skipped"
+                            ),
+                        );
+                    }
+                    surface
+                }
+                None => {
+                    self.warning(
+                        "unmapped-material",
+                        format!("mesh material {ix} has no name, so its geometry is skipped"),
+                    );
+                    None
+                }
+            };
+            used.extend(surface.map(|(name, _, _)| name));
+            surfaces.push(surface);
+        }
+
+        let mut unused: Vec<_> = manifest
+            .level()
+            .surface_iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| !used.contains(name))
+            .collect();
+        unused.sort();
+        for name in unused {
+            self.warning(
+                "unused-surface",
+                format!("surface \"{name}\" is not used by any mesh material"),
+            );
+        }
+
+        let mut textures = LintTextures {
+            refs: HashMap::new(),
+            next_free: [0; TEXTURE_BUCKETS.len()],
+            full: [false; TEXTURE_BUCKETS.len()],
+        };
+        let mut material_index = Some(MaterialIndexStorageBufferData::new());
+        for (ix, surface) in surfaces.iter().enumerate() {
+            let Some((name, surface_url, surface)) = surface else {
+                continue;
+            };
+            let (frames, speed, color, unlit) = match surface {
+                LevelManifestSurface::TextureSingle {
+                    frame,
+                    color,
+                    unlit,
+                    ..
+                } => (
+                    std::slice::from_ref(frame),
+                    STATIC_ANIMATION_SPEED,
+                    color.unwrap_or(Color::WHITE),
+                    unlit.unwrap_or(false),
+                ),
+                LevelManifestSurface::TextureMulti {
+                    frames,
+                    animation_speed,
+                    color,
+                    unlit,
+                    ..
+                } => (
+                    frames.as_slice(),
+                    *animation_speed,
+                    color.unwrap_or(Color::WHITE),
+                    unlit.unwrap_or(false),
+                ),
+                LevelManifestSurface::Untextured { color, unlit, .. } => (
+                    &[][..],
+                    STATIC_ANIMATION_SPEED,
+                    *color,
+                    unlit.unwrap_or(false),
+                ),
+                LevelManifestSurface::Invisible { .. } => continue,
+            };
+            let owner = format!("surface \"{name}\"");
+            let refs = self.frames_lint(&owner, surface_url, batch, frames, &mut textures);
+            self.material_write(&mut material_index, ix, speed, &refs, color, unlit);
+        }
+
+        // The lightmap takes the material slot after the last mesh material.
+        let lightmap: Vec<String> = manifest
+            .level()
+            .lightmap()
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+        let refs = self.frames_lint("lightmap", base_url, batch, &lightmap, &mut textures);
+        self.material_write(
+            &mut material_index,
+            surfaces.len(),
+            STATIC_ANIMATION_SPEED,
+            &refs,
+            Color::WHITE,
+            false,
+        );
+    }
+
+    fn frames_lint(
+        &mut self,
+        owner: &str,
+        base_url: &Url,
+        batch: &FetchBatch,
+        frames: &[String],
+        textures: &mut LintTextures,
+    ) -> Vec<MaterialTextureRef> {
+        let mut refs = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let key = base_url
+                .join(frame)
+                .map_or_else(|_| frame.clone(), String::from);
+            let texture_ref = match textures.refs.get(&key) {
+                Some(texture_ref) => *texture_ref,
+                None => {
+                    let texture_ref = self.frame_lint(owner, base_url, batch, frame, textures);
+                    textures.refs.insert(key, texture_ref);
+                    texture_ref
+                }
+            };
+            refs.extend(texture_ref);
+        }
+        return refs;
+    }
+
+    fn frame_lint(
+        &mut self,
+        owner: &str,
+        base_url: &Url,
+        batch: &FetchBatch,
+        frame: &str,
+        textures: &mut LintTextures,
+    ) -> Option<MaterialTextureRef> {
+        let (url, data) = self.asset_get("texture", base_url, batch, frame)?;
+        let image = match image::load_from_memory(data) {
+            Ok(image) => image,
+            Err(err) => {
+                self.error(
+                    "texture",
+                    format!(
+                        "{owner}: failed to decode image {url}: {}",
+                        error_chain(&err)
+                    ),
+                );
+                return None;
+            }
+        };
+
+        let fit_ix = texture_bucket_fit(image.width(), image.height());
+        let Some((bucket_ix, layer)) = texture_bucket_alloc(&mut textures.next_free, fit_ix) else {
+            if !textures.full[fit_ix] {
+                textures.full[fit_ix] = true;
+                let bucket = TEXTURE_BUCKETS[fit_ix];
+                let (w, h) = (bucket.width, bucket.height);
+                self.error(
+                    "texture-count",
+                    format!(
+                        "{owner}: image {url} needs a {w}x{h} texture, but every {w}x{h} and \
+                         larger texture slot is taken"
+                    ),
+                );
+            }
+            return None;
+        };
+        return Some(MaterialTextureRef {
+            bucket: bucket_ix as u16,
+            layer: layer as u16,
+        });
+    }
+
+    fn sky_lint(&mut self, base_url: &Url, batch: &FetchBatch, sky: &LevelManifestSky) {
+        let mut images = Vec::new();
+        for href in sky.hrefs() {
+            let Some((url, data)) = self.asset_get("sky", base_url, batch, href) else {
+                return;
+            };
+            match image::load_from_memory(data) {
+                Ok(image) => images.push(image.to_rgba8()),
+                Err(err) => {
+                    self.error(
+                        "sky",
+                        format!("failed to decode image {url}: {}", error_chain(&err)),
+                    );
+                    return;
+                }
+            }
+        }
+
+        let size = match sky {
+            LevelManifestSky::Equirectangular { .. } => sky_equirectangular_size(&images[0]),
+            LevelManifestSky::Cubemap { .. } => sky_cubemap_size(&images.try_into().unwrap()),
+        };
+        if let Err(err) = size {
+            self.error("sky", err.to_string());
+        }
+    }
+
+    // Writes through the game's own material index so its limits apply unchanged. Only the
+    // first failure is reported, as later writes fail for the same reason.
+    fn material_write(
+        &mut self,
+        material_index: &mut Option<MaterialIndexStorageBufferData>,
+        ix: usize,
+        speed: f32,
+        refs: &[MaterialTextureRef],
+        color: Color,
+        unlit: bool,
+    ) {
+        let Some(data) = material_index else {
+            return;
+        };
+        if let Err(err) = data.write(ix as u32, speed, refs, color, unlit) {
+            self.error("material-limit", error_chain(&err));
+            *material_index = None;
+        }
+    }
+
+    fn geometry_get(
+        &mut self,
+        check: &'static str,
+        owner: &str,
+        base_url: &Url,
+        batch: &FetchBatch,
+        mesh_href: &str,
+    ) -> Option<LevelPortalGeometry> {
+        let mesh = self.mesh_get(check, base_url, batch, mesh_href)?;
+        return match LevelPortalGeometry::from_gltf(mesh.vertices()) {
+            Ok(geometry) => Some(geometry),
+            Err(err) => {
+                self.error(
+                    check,
+                    format!("{owner}: invalid portal geometry: {}", error_chain(&err)),
+                );
+                None
+            }
+        };
+    }
+
+    fn link_lint(&mut self, name: &str, link_href: &str, geometry: Option<&LevelPortalGeometry>) {
+        let Ok(link) = self.url.join(link_href) else {
+            self.error(
+                "link",
+                format!("portal \"{name}\" has invalid link \"{link_href}\""),
+            );
+            return;
+        };
+        if let Some(spawn_name) = LevelManifestSpawn::fragment_name(&link) {
+            self.spawn_link_lint(name, &link, &spawn_name);
+            return;
+        }
+        let Some(dst_name) = link.fragment().filter(|fragment| !fragment.is_empty()) else {
+            self.error(
+                "link",
+                format!("portal \"{name}\" link {link} has no #portal fragment"),
+            );
+            return;
+        };
+        let dst_name = dst_name.to_string();
+        let mut dst_url = link.clone();
+        dst_url.set_fragment(None);
+
+        let mut dst = match LintSource::load(&dst_url, &self.limits) {
+            Ok(dst) => dst,
+            Err(err) => {
+                self.error(
+                    "link",
+                    format!(
+                        "portal \"{name}\" links to {dst_url}, which failed to load: {}",
+                        error_chain(&err)
+                    ),
+                );
+                return;
+            }
+        };
+        let Some((_, dst_portal)) = dst
+            .manifest
+            .portal_iter()
+            .find(|(portal_name, _)| **portal_name == dst_name)
+        else {
+            self.error(
+                "link",
+                format!("portal \"{name}\" links to missing portal \"{dst_name}\" in {dst_url}"),
+            );
+            return;
+        };
+        let dst_mesh_href = dst_portal.mesh().to_string();
+
+        let mut back = self.url.clone();
+        back.set_fragment(Some(name));
+        if dst_url.join(dst_portal.link_href()).ok() != Some(back) {
+            self.warning(
+                "link-reciprocity",
+                format!(
+                    "portal \"{name}\" links to \"{dst_name}\" in {dst_url}, which does not link \
+                     back"
+                ),
+            );
+        }
+
+        let Some(geometry) = geometry else {
+            return;
+        };
+        let dst_base_url = dst.base_url.clone();
+        let dst_mesh_urls = dst_base_url.join(&dst_mesh_href).into_iter().collect();
+        let dst_batch = dst.batch(dst_mesh_urls, &self.limits);
+        let owner = format!("portal \"{dst_name}\" in {dst_url}");
+        let Some(dst_geometry) =
+            self.geometry_get("link", &owner, &dst_base_url, &dst_batch, &dst_mesh_href)
+        else {
+            return;
+        };
+        if !geometry.matches(&dst_geometry) {
+            self.error(
+                "link-fingerprint",
+                format!(
+                    "portal \"{name}\" does not match the shape or orientation of \"{dst_name}\" \
+                     in {dst_url}"
+                ),
+            );
+        }
+    }
+
+    // The game falls back to the level spawn when the named one is missing.
+    fn spawn_link_lint(&mut self, name: &str, link: &Url, spawn_name: &str) {
+        let mut dst_url = link.clone();
+        dst_url.set_fragment(None);
+
+        let dst = match LintSource::load(&dst_url, &self.limits) {
+            Ok(dst) => dst,
+            Err(err) => {
+                self.error(
+                    "link",
+                    format!(
+                        "portal \"{name}\" links to {dst_url}, which failed to load: {}",
+                        error_chain(&err)
+                    ),
+                );
+                return;
+            }
+        };
+        if !dst
+            .manifest
+            .spawn_iter()
+            .any(|(dst_spawn_name, _)| dst_spawn_name == spawn_name)
+        {
+            self.warning(
+                "link",
+                format!("portal \"{name}\" links to missing spawn \"{spawn_name}\" in {dst_url}"),
+            );
+        }
+    }
+
+    // Only levels in a local directory can be listed; a hosted level's files are unknown.
+    fn unreferenced_lint(&mut self, asset_urls: &[Url]) {
+        if self.url.scheme() != "file" || FetchBundle::is_bundle(&self.url) {
+            return;
+        }
+        let Ok(manifest_path) = self.url.to_file_path() else {
+            return;
+        };
+        let Some(dir) = manifest_path.parent() else {
+            return;
+        };
+
+        let referenced: HashSet<PathBuf> = asset_urls
+            .iter()
+            .filter_map(|url| url.to_file_path().ok())
+            .chain([manifest_path.clone()])
+            .collect();
+        let mut files = Vec::new();
+        files_walk(dir, true, &mut files);
+        files.retain(|file| !referenced.contains(file));
+        files.sort();
+        for file in files {
+            let relative = file.strip_prefix(dir).unwrap_or(&file);
+            self.warning(
+                "unreferenced-file",
+                format!("{} is not referenced by the manifest", relative.display()),
+            );
+        }
+    }
+}
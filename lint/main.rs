@@ -0,0 +1,91 @@
+// Checks a level the way the game loads it, plus problems the game silently tolerates,
+// so authors can catch them before publishing.
+//
+//     cargo run --bin halls-lint -- <manifest-path-or-url> [--json] [--deny-warnings]
+//     cargo run --bin halls-lint -- schema
+//
+// Exits non-zero when an error is found, or a warning when `--deny-warnings` is given.
+// `schema` prints the manifest JSON Schema instead.
+
+mod lint;
+mod schema;
+
+#[cfg(test)]
+#[path = "../test/stand_in.rs"]
+mod stand_in;
+#[cfg(test)]
+mod test;
+
+use std::process::ExitCode;
+
+use url::Url;
+
+use halls::level::fetch::FetchLimits;
+use lint::{Lint, LintSeverity};
+
+const USAGE: &str =
+    "usage: halls-lint <manifest-path-or-url> [--json] [--deny-warnings] | halls-lint schema";
+
+fn main() -> ExitCode {
+    let mut positional = Vec::new();
+    let mut json = false;
+    let mut deny_warnings = false;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--deny-warnings" => deny_warnings = true,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [target] = positional.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    if target == "schema" {
+        print!("{}", schema::manifest_schema());
+        return ExitCode::SUCCESS;
+    }
+    let url = match Url::parse(target) {
+        Ok(url) => url,
+        Err(_) => match std::path::absolute(target).map(Url::from_file_path) {
+            Ok(Ok(url)) => url,
+            _ => {
+                eprintln!("invalid path or URL {target}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let findings = Lint::run(url.clone(), FetchLimits::default());
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == LintSeverity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+
+    if json {
+        let report = serde_json::json!({
+            "url": url.as_str(),
+            "errors": errors,
+            "warnings": warnings,
+            "findings": findings,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        for finding in &findings {
+            let severity = match finding.severity {
+                LintSeverity::Error => "error",
+                LintSeverity::Warning => "warning",
+            };
+            println!("{severity}[{}]: {}", finding.check, finding.message);
+        }
+        println!("{url}: {errors} error(s), {warnings} warning(s)");
+    }
+
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
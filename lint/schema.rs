@@ -0,0 +1,9 @@
+use schemars::schema_for;
+
+use halls::level::manifest::LevelManifest;
+
+// JSON Schema for the current manifest version, as checked in at `manifest.schema.json`.
+pub fn manifest_schema() -> String {
+    let schema = schema_for!(LevelManifest);
+    return format!("{}\n", serde_json::to_string_pretty(&schema).unwrap());
+}
@@ -0,0 +1,507 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use url::Url;
+
+use super::lint::{Lint, LintFinding, LintSeverity};
+use super::stand_in::stand_in_serve;
+use halls::level::fetch::FetchLimits;
+
+const MAGENTA: [f32; 4] = [1.0, 0.0, 1.0, 1.0];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+// A binary glTF holding one unindexed primitive.
+fn glb(
+    positions: &[[f32; 3]],
+    colors: Option<&[[f32; 4]]>,
+    lightmap_uvs: bool,
+    material: Option<&str>,
+) -> Vec<u8> {
+    let mut bin: Vec<u8> = Vec::new();
+    let mut views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut attributes = serde_json::Map::new();
+
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for position in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(position[axis]);
+            max[axis] = max[axis].max(position[axis]);
+        }
+    }
+    let mut attribute_push = |name: &str, kind: &str, values: Vec<f32>, bounds: bool| {
+        let offset = bin.len();
+        bin.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+        views.push(json!({ "buffer": 0, "byteOffset": offset, "byteLength": values.len() * 4 }));
+        let mut accessor = json!({
+            "bufferView": views.len() - 1,
+            "componentType": 5126,
+            "count": positions.len(),
+            "type": kind,
+        });
+        if bounds {
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        accessors.push(accessor);
+        attributes.insert(name.to_string(), json!(accessors.len() - 1));
+    };
+
+    attribute_push("POSITION", "VEC3", positions.concat(), true);
+    if let Some(colors) = colors {
+        attribute_push("COLOR_0", "VEC4", colors.concat(), false);
+    }
+    if lightmap_uvs {
+        attribute_push("TEXCOORD_1", "VEC2", vec![0.0; positions.len() * 2], false);
+    }
+
+    let mut primitive = json!({ "attributes": attributes });
+    let mut document = json!({
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "buffers": [{ "byteLength": bin.len() }],
+        "bufferViews": views,
+        "accessors": accessors,
+    });
+    if let Some(material) = material {
+        primitive["material"] = json!(0);
+        document["materials"] = json!([{ "name": material }]);
+    }
+    document["meshes"] = json!([{ "primitives": [primitive] }]);
+
+    let mut document = serde_json::to_vec(&document).unwrap();
+    document.resize(document.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let mut data = Vec::new();
+    let length = 12 + 8 + document.len() + 8 + bin.len();
+    data.extend(b"glTF");
+    data.extend(2u32.to_le_bytes());
+    data.extend((length as u32).to_le_bytes());
+    data.extend((document.len() as u32).to_le_bytes());
+    data.extend(b"JSON");
+    data.extend(document);
+    data.extend((bin.len() as u32).to_le_bytes());
+    data.extend(b"BIN\0");
+    data.extend(bin);
+    return data;
+}
+
+fn level_glb(material: &str, lightmap_uvs: bool) -> Vec<u8> {
+    let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+    return glb(&positions, None, lightmap_uvs, Some(material));
+}
+
+// A wall portal whose anchor is its bottom-left corner.
+fn door_glb() -> Vec<u8> {
+    let positions = [
+        [-1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 2.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        [1.0, 2.0, 0.0],
+        [-1.0, 2.0, 0.0],
+    ];
+    let colors = [MAGENTA, WHITE, WHITE, MAGENTA, WHITE, WHITE];
+    return glb(&positions, Some(&colors), false, None);
+}
+
+fn triangle_door_glb() -> Vec<u8> {
+    let positions = [[-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]];
+    let colors = [MAGENTA, WHITE, WHITE];
+    return glb(&positions, Some(&colors), false, None);
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = Cursor::new(Vec::new());
+    image::RgbaImage::new(width, height)
+        .write_to(&mut data, image::ImageFormat::Png)
+        .unwrap();
+    return data.into_inner();
+}
+
+fn manifest_json(surface: &str, link: Option<&str>, lightmap: Option<&str>) -> Vec<u8> {
+    let mut manifest = json!({
+        "_version": "coco",
+        "meta": { "name": "Hall" },
+        "level": {
+            "mesh": "mesh.glb",
+            "surface": { surface: { "type": "TextureSingle", "frame": "wall.png" } }
+        },
+        "portal": {}
+    });
+    if let Some(link) = link {
+        manifest["portal"]["door"] = json!({ "mesh": "door.glb", "link": link });
+    }
+    if let Some(lightmap) = lightmap {
+        manifest["level"]["lightmap"] = json!(lightmap);
+    }
+    return serde_json::to_vec(&manifest).unwrap();
+}
+
+fn halls_write(name: &str, files: Vec<(&str, Vec<u8>)>) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("halls-lint-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, data) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+    return dir;
+}
+
+// Two halls joined by a reciprocal pair of wall doors.
+fn halls_linked() -> Vec<(&'static str, Vec<u8>)> {
+    return vec![
+        (
+            "a/manifest.json",
+            manifest_json("Wall", Some("../b/manifest.json#door"), None),
+        ),
+        ("a/mesh.glb", level_glb("Wall", false)),
+        ("a/door.glb", door_glb()),
+        ("a/wall.png", png(64, 64)),
+        (
+            "b/manifest.json",
+            manifest_json("Wall", Some("../a/manifest.json#door"), None),
+        ),
+        ("b/mesh.glb", level_glb("Wall", false)),
+        ("b/door.glb", door_glb()),
+        ("b/wall.png", png(64, 64)),
+    ];
+}
+
+fn hall_lint(dir: &Path, name: &str) -> Vec<LintFinding> {
+    let url = Url::from_file_path(dir.join(name).join("manifest.json")).unwrap();
+    return Lint::run(url, FetchLimits::default());
+}
+
+fn checks(findings: &[LintFinding], severity: LintSeverity) -> Vec<&'static str> {
+    return findings
+        .iter()
+        .filter(|finding| finding.severity == severity)
+        .map(|finding| finding.check)
+        .collect();
+}
+
+#[test]
+fn test_lint_linked_halls_have_no_findings() {
+    let dir = halls_write("linked", halls_linked());
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_lint_linked_halls_over_http_have_no_findings() {
+    let stand_in = stand_in_serve(halls_linked());
+    let url = stand_in.url.join("a/manifest.json").unwrap();
+
+    let findings = Lint::run(url, FetchLimits::default());
+
+    assert!(findings.is_empty(), "{findings:?}");
+    let requests = stand_in.requests.lock().unwrap();
+    assert!(requests.iter().any(|path| path == "b/manifest.json"));
+}
+
+#[test]
+fn test_lint_reports_manifest_decode_error() {
+    let dir = halls_write("decode", vec![("a/manifest.json", b"{}".to_vec())]);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["manifest"]);
+}
+
+#[test]
+fn test_lint_accepts_any_texture_size() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/wall.png");
+    files.push(("a/wall.png", png(96, 40)));
+    let dir = halls_write("texture-size", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_lint_reports_texture_count_once_larger_slots_are_taken() {
+    let mut a_manifest: serde_json::Value = serde_json::from_slice(&manifest_json(
+        "Wall",
+        Some("../b/manifest.json#door"),
+        None,
+    ))
+    .unwrap();
+    a_manifest["level"]["surface"]["Wall"] = json!({
+        "type": "TextureMulti",
+        "frames": ["wall.png", "big.png", "bigger.png"],
+        "animation_speed": 1.0
+    });
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push(("a/manifest.json", serde_json::to_vec(&a_manifest).unwrap()));
+    files.push(("a/big.png", png(1100, 1100)));
+    files.push(("a/bigger.png", png(2048, 1200)));
+    let dir = halls_write("texture-count", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["texture-count"]);
+    assert!(findings[0].message.contains("bigger.png"));
+    assert!(findings[0].message.contains("2048x2048"));
+}
+
+#[test]
+fn test_lint_warns_unmapped_material_and_unused_surface() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/mesh.glb");
+    files.push(("a/mesh.glb", level_glb("Floor", false)));
+    let dir = halls_write("unmapped", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(
+        checks(&findings, LintSeverity::Warning),
+        ["unmapped-material", "unused-surface"]
+    );
+    assert!(checks(&findings, LintSeverity::Error).is_empty());
+}
+
+#[test]
+fn test_lint_warns_missing_lightmap_uvs() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push((
+        "a/manifest.json",
+        manifest_json("Wall", Some("../b/manifest.json#door"), Some("wall.png")),
+    ));
+    let dir = halls_write("lightmap", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Warning), ["lightmap-uv"]);
+}
+
+#[test]
+fn test_lint_reports_fingerprint_mismatch() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "b/door.glb");
+    files.push(("b/door.glb", triangle_door_glb()));
+    let dir = halls_write("fingerprint", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["link-fingerprint"]);
+}
+
+#[test]
+fn test_lint_reports_missing_destination_portal() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push((
+        "a/manifest.json",
+        manifest_json("Wall", Some("../b/manifest.json#window"), None),
+    ));
+    let dir = halls_write("missing-portal", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["link"]);
+}
+
+#[test]
+fn test_lint_warns_one_way_link() {
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "b/manifest.json");
+    files.push((
+        "b/manifest.json",
+        manifest_json("Wall", Some("../c/manifest.json#door"), None),
+    ));
+    let dir = halls_write("one-way", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(
+        checks(&findings, LintSeverity::Warning),
+        ["link-reciprocity"]
+    );
+    assert!(checks(&findings, LintSeverity::Error).is_empty());
+}
+
+#[test]
+fn test_lint_checks_spawn_link_destination() {
+    let mut b_manifest: serde_json::Value =
+        serde_json::from_slice(&manifest_json("Wall", None, None)).unwrap();
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json" && *path != "b/manifest.json");
+    files.push((
+        "a/manifest.json",
+        manifest_json("Wall", Some("../b/manifest.json#spawn=garden"), None),
+    ));
+    files.push(("b/manifest.json", serde_json::to_vec(&b_manifest).unwrap()));
+    let dir = halls_write("spawn-missing", files.clone());
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Warning), ["link"]);
+    assert!(findings[0].message.contains("missing spawn \"garden\""));
+
+    b_manifest["spawn"] = json!({ "garden": { "position": [0.0, 0.0, 0.0], "yaw": 90.0 } });
+    files.retain(|(path, _)| *path != "b/manifest.json");
+    files.push(("b/manifest.json", serde_json::to_vec(&b_manifest).unwrap()));
+    let dir = halls_write("spawn", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_lint_reports_unsupported_sky_size() {
+    let mut a_manifest: serde_json::Value = serde_json::from_slice(&manifest_json(
+        "Wall",
+        Some("../b/manifest.json#door"),
+        None,
+    ))
+    .unwrap();
+    a_manifest["level"]["environment"] =
+        json!({ "sky": { "type": "Equirectangular", "image": "sky.png" } });
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push(("a/manifest.json", serde_json::to_vec(&a_manifest).unwrap()));
+    files.push(("a/sky.png", png(100, 100)));
+    let dir = halls_write("sky-size", files.clone());
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["sky"]);
+    assert!(findings[0].message.contains("100x100"));
+
+    files.retain(|(path, _)| *path != "a/sky.png");
+    files.push(("a/sky.png", png(64, 32)));
+    let dir = halls_write("sky", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_lint_warns_unknown_manifest_fields() {
+    let mut a_manifest: serde_json::Value = serde_json::from_slice(&manifest_json(
+        "Wall",
+        Some("../b/manifest.json#door"),
+        None,
+    ))
+    .unwrap();
+    a_manifest["level"]["surface"]["Wall"]["unlitt"] = json!(true);
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push(("a/manifest.json", serde_json::to_vec(&a_manifest).unwrap()));
+    let dir = halls_write("unknown-field", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Warning), ["unknown-field"]);
+    assert!(findings[0].message.contains("level.surface.Wall.unlitt"));
+}
+
+#[test]
+fn test_lint_warns_of_undecodable_thumbnail() {
+    let mut a_manifest: serde_json::Value = serde_json::from_slice(&manifest_json(
+        "Wall",
+        Some("../b/manifest.json#door"),
+        None,
+    ))
+    .unwrap();
+    a_manifest["meta"]["thumbnail"] = json!("thumbnail.png");
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json");
+    files.push(("a/manifest.json", serde_json::to_vec(&a_manifest).unwrap()));
+    files.push(("a/thumbnail.png", b"not an image".to_vec()));
+    let dir = halls_write("thumbnail", files.clone());
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(checks(&findings, LintSeverity::Error).is_empty());
+    assert_eq!(checks(&findings, LintSeverity::Warning), ["thumbnail"]);
+
+    files.retain(|(path, _)| *path != "a/thumbnail.png");
+    files.push(("a/thumbnail.png", png(320, 180)));
+    let dir = halls_write("thumbnail", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn test_lint_checks_surface_library_frames_and_cycles() {
+    let mut a_manifest: serde_json::Value = serde_json::from_slice(&manifest_json(
+        "Wall",
+        Some("../b/manifest.json#door"),
+        None,
+    ))
+    .unwrap();
+    a_manifest["level"]["surface"] = json!({});
+    a_manifest["level"]["surface_library"] = json!(["surfaces.json"]);
+    let library = json!({
+        "surface_library": ["../shared/surfaces.json"],
+        "surface": {}
+    });
+    let shared = json!({
+        "surface": { "Wall": { "type": "TextureSingle", "frame": "brick.png" } }
+    });
+    let mut files = halls_linked();
+    files.retain(|(path, _)| *path != "a/manifest.json" && *path != "a/wall.png");
+    files.push(("a/manifest.json", serde_json::to_vec(&a_manifest).unwrap()));
+    files.push(("a/surfaces.json", serde_json::to_vec(&library).unwrap()));
+    files.push(("shared/surfaces.json", serde_json::to_vec(&shared).unwrap()));
+    files.push(("shared/brick.png", b"not an image".to_vec()));
+    let dir = halls_write("surface-library", files.clone());
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["texture"]);
+    assert!(findings[0].message.contains("shared/brick.png"));
+
+    let cycle = json!({ "surface_library": ["../a/surfaces.json"], "surface": {} });
+    files.retain(|(path, _)| *path != "shared/surfaces.json");
+    files.push(("shared/surfaces.json", serde_json::to_vec(&cycle).unwrap()));
+    let dir = halls_write("surface-library", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(checks(&findings, LintSeverity::Error), ["surface-library"]);
+    assert!(findings[0].message.contains("import each other"));
+}
+
+#[test]
+fn test_lint_warns_unreferenced_files_but_not_nested_levels() {
+    let mut files = halls_linked();
+    files.push(("a/notes.txt", b"todo".to_vec()));
+    files.push(("a/nested/manifest.json", b"{}".to_vec()));
+    let dir = halls_write("unreferenced", files);
+
+    let findings = hall_lint(&dir, "a");
+
+    assert_eq!(
+        checks(&findings, LintSeverity::Warning),
+        ["unreferenced-file"]
+    );
+    assert!(findings[0].message.starts_with("notes.txt"));
+}
+
+#[test]
+fn test_manifest_schema_is_up_to_date() {
+    assert!(
+        super::schema::manifest_schema() == include_str!("../manifest.schema.json"),
+        "regenerate with `cargo run --bin halls-lint -- schema > manifest.schema.json`"
+    );
+}
@@ -0,0 +1,95 @@
+// Crawls a portal graph from a manifest URL into a directory that can be opened via
+// `file://` without a network connection.
+//
+//     cargo run --bin mirror -- <manifest-url> <out-dir> [--depth N] [--allow HOST[:PORT]]...
+//
+// Only levels on allowed hosts (the root's host by default) are followed; links past
+// the depth limit or off the allowlist are left pointing at the original URL. Assets
+// already on disk are skipped, so an interrupted run can be resumed.
+
+mod mirror;
+
+#[cfg(test)]
+#[path = "../test/stand_in.rs"]
+mod stand_in;
+#[cfg(test)]
+mod test;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use url::Url;
+
+use halls::level::fetch::FetchLimits;
+use mirror::{Mirror, MirrorOptions};
+
+const USAGE: &str = "usage: mirror <manifest-url> <out-dir> [--depth N] [--allow HOST[:PORT]]...";
+
+fn main() -> ExitCode {
+    let mut positional = Vec::new();
+    let mut max_depth = usize::MAX;
+    let mut allow = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                let Some(depth) = args.next().and_then(|depth| depth.parse().ok()) else {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                };
+                max_depth = depth;
+            }
+            "--allow" => {
+                let Some(host) = args.next() else {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                };
+                allow.push(host);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let [url, out_dir] = positional.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(err) => {
+            eprintln!("invalid URL {url}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if allow.is_empty() {
+        allow.extend(url.host_str().map(str::to_string));
+    }
+
+    let options = MirrorOptions {
+        max_depth,
+        allow,
+        limits: FetchLimits::default(),
+    };
+    let mut mirror = Mirror::new(url.clone(), PathBuf::from(out_dir), options);
+    let errors = mirror.run();
+    for err in &errors {
+        let mut message = err.to_string();
+        let mut source = std::error::Error::source(err);
+        while let Some(err) = source {
+            message = format!("{message}: {err}");
+            source = err.source();
+        }
+        eprintln!("{message}");
+    }
+
+    // An unsafe root path was already reported by the run.
+    if let Ok(root) = mirror.path(&url) {
+        let root = std::path::absolute(root).unwrap();
+        println!("open {}", Url::from_file_path(root).unwrap());
+    }
+    if !errors.is_empty() {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
@@ -0,0 +1,350 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+use url::Url;
+
+use halls::level::fetch::{fetch, FetchBundle, FetchError, FetchLimits};
+use halls::level::manifest::{LevelManifest, LevelManifestLoadError};
+
+const INDEX_NAME: &str = "index";
+const PART_EXTENSION: &str = "part";
+const LEVEL_ASSET_KEYS: [&str; 3] = ["mesh", "lightmap", "track"];
+
+pub struct MirrorOptions {
+    pub max_depth: usize,
+    pub allow: Vec<String>,
+    pub limits: FetchLimits,
+}
+
+#[derive(Debug)]
+pub enum MirrorError {
+    Fetch(String, FetchError),
+    Manifest(String, LevelManifestLoadError),
+    Library(String, serde_json::Error),
+    // A URL segment that would land outside the mirror directory, such as `..%2F..`.
+    Path(String),
+    IO(String, io::Error),
+}
+
+impl std::fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            MirrorError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            MirrorError::Manifest(url, _) => write!(f, "failed to load manifest {url}"),
+            MirrorError::Library(url, _) => write!(f, "invalid surface library {url}"),
+            MirrorError::Path(url) => write!(f, "unsafe path in {url}"),
+            MirrorError::IO(path, _) => write!(f, "failed to write {path}"),
+        };
+    }
+}
+
+impl std::error::Error for MirrorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            MirrorError::Fetch(_, err) => Some(err),
+            MirrorError::Manifest(_, err) => Some(err),
+            MirrorError::Library(_, err) => Some(err),
+            MirrorError::IO(_, err) => Some(err),
+            MirrorError::Path(_) => None,
+        };
+    }
+}
+
+// Where `url` lives inside the mirror, as percent-encoded segments starting with the host.
+// Encoded segments double as relative hrefs; files on disk use the decoded names.
+fn mirror_segments(url: &Url) -> Vec<String> {
+    let mut host = url.host_str().unwrap_or_default().to_string();
+    if let Some(port) = url.port() {
+        host = format!("{host}_{port}");
+    }
+    let mut segments = vec![host];
+    segments.extend(
+        url.path_segments()
+            .into_iter()
+            .flatten()
+            .map(str::to_string),
+    );
+    if let Some(last) = segments.last_mut().filter(|last| last.is_empty()) {
+        *last = INDEX_NAME.to_string();
+    }
+    return segments;
+}
+
+// Relative href from the file at `from` to the file at `to`.
+fn mirror_href(from: &[String], to: &[String]) -> String {
+    let from_dir = &from[..from.len() - 1];
+    let common = from_dir
+        .iter()
+        .zip(&to[..to.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+    parts.extend(to[common..].iter().map(String::as_str));
+    return parts.join("/");
+}
+
+pub struct Mirror {
+    out_dir: PathBuf,
+    options: MirrorOptions,
+    visited: HashSet<Url>,
+    queue: VecDeque<(Url, usize)>,
+    libraries: HashSet<Url>,
+}
+
+impl Mirror {
+    pub fn new(root: Url, out_dir: PathBuf, options: MirrorOptions) -> Self {
+        let mut root = root;
+        root.set_fragment(None);
+        return Self {
+            out_dir,
+            options,
+            visited: HashSet::from([root.clone()]),
+            queue: VecDeque::from([(root, 0)]),
+            libraries: HashSet::new(),
+        };
+    }
+
+    // Segments that decode to a separator or a dot entry are refused rather than written,
+    // since pushing them would step outside `out_dir`.
+    pub fn path(&self, url: &Url) -> Result<PathBuf, MirrorError> {
+        let mut path = self.out_dir.clone();
+        for segment in mirror_segments(url) {
+            let name = percent_decode_str(&segment).decode_utf8_lossy();
+            if name == "." || name == ".." || name.contains(['/', '\\']) {
+                return Err(MirrorError::Path(url.to_string()));
+            }
+            path.push(name.as_ref());
+        }
+        return Ok(path);
+    }
+
+    fn allowed(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host_port = url.port().map(|port| format!("{host}:{port}"));
+        return self
+            .options
+            .allow
+            .iter()
+            .any(|allow| allow == host || Some(allow) == host_port.as_ref());
+    }
+
+    fn write(&self, url: &Url, data: &[u8]) -> Result<(), MirrorError> {
+        let path = self.path(url)?;
+        let part = path.with_extension(PART_EXTENSION);
+        let io_error = |err| MirrorError::IO(path.display().to_string(), err);
+        fs::create_dir_all(path.parent().unwrap()).map_err(io_error)?;
+        // Written aside and renamed, so a file on disk is always complete.
+        fs::write(&part, data).map_err(io_error)?;
+        fs::rename(&part, &path).map_err(io_error)?;
+        return Ok(());
+    }
+
+    // Downloads `url` unless an earlier run already did.
+    fn asset_mirror(&self, url: &Url) -> Result<(), MirrorError> {
+        if self.path(url)?.exists() {
+            return Ok(());
+        }
+        let data = fetch(url, &self.options.limits, None)
+            .map_err(|err| MirrorError::Fetch(url.to_string(), err))?;
+        return self.write(url, &data);
+    }
+
+    fn href_mirror(&self, base_url: &Url, href: &mut Value) -> Result<(), MirrorError> {
+        let Some(url) = href.as_str().and_then(|href| base_url.join(href).ok()) else {
+            return Ok(());
+        };
+        self.asset_mirror(&url)?;
+        *href = Value::String(mirror_href(
+            &mirror_segments(base_url),
+            &mirror_segments(&url),
+        ));
+        return Ok(());
+    }
+
+    fn surfaces_mirror(
+        &self,
+        base_url: &Url,
+        surfaces: Option<&mut Value>,
+    ) -> Result<(), MirrorError> {
+        let surfaces = surfaces.and_then(Value::as_object_mut);
+        for surface in surfaces
+            .into_iter()
+            .flat_map(|surfaces| surfaces.values_mut())
+        {
+            if let Some(frame) = surface.get_mut("frame") {
+                self.href_mirror(base_url, frame)?;
+            }
+            let frames = surface.get_mut("frames").and_then(Value::as_array_mut);
+            for frame in frames.into_iter().flatten() {
+                self.href_mirror(base_url, frame)?;
+            }
+        }
+        return Ok(());
+    }
+
+    fn libraries_mirror(
+        &mut self,
+        base_url: &Url,
+        hrefs: Option<&mut Value>,
+    ) -> Result<(), MirrorError> {
+        for href in hrefs.and_then(Value::as_array_mut).into_iter().flatten() {
+            let Some(url) = href.as_str().and_then(|href| base_url.join(href).ok()) else {
+                continue;
+            };
+            self.library_mirror(&url)?;
+            *href = Value::String(mirror_href(
+                &mirror_segments(base_url),
+                &mirror_segments(&url),
+            ));
+        }
+        return Ok(());
+    }
+
+    // Surface libraries are rewritten like manifests. Each is written once per run, which
+    // also ends import cycles.
+    fn library_mirror(&mut self, url: &Url) -> Result<(), MirrorError> {
+        if !self.libraries.insert(url.clone()) {
+            return Ok(());
+        }
+        let data = fetch(url, &self.options.limits, None)
+            .map_err(|err| MirrorError::Fetch(url.to_string(), err))?;
+        let mut value: Value = serde_json::from_slice(&data)
+            .map_err(|err| MirrorError::Library(url.to_string(), err))?;
+        self.surfaces_mirror(url, value.get_mut("surface"))?;
+        self.libraries_mirror(url, value.get_mut("surface_library"))?;
+        return self.write(url, &serde_json::to_vec_pretty(&value).unwrap());
+    }
+
+    // Follows `link` when it is in range, returning the href the mirrored manifest
+    // should use: relative when the destination is mirrored, absolute otherwise.
+    fn link_mirror(&mut self, base_url: &Url, link: &Url, depth: usize) -> String {
+        let mut level_url = link.clone();
+        level_url.set_fragment(None);
+        let mirrored = depth < self.options.max_depth
+            && self.allowed(&level_url)
+            && self.path(&level_url).is_ok();
+        if !mirrored {
+            return link.to_string();
+        }
+        if self.visited.insert(level_url.clone()) {
+            self.queue.push_back((level_url.clone(), depth + 1));
+        }
+
+        let mut href = mirror_href(&mirror_segments(base_url), &mirror_segments(&level_url));
+        if let Some(fragment) = link.fragment() {
+            href = format!("{href}#{fragment}");
+        }
+        return href;
+    }
+
+    // Bundles are copied verbatim; their portal links are followed but not rewritten.
+    fn bundle_mirror(&mut self, url: &Url, depth: usize) -> Result<(), MirrorError> {
+        let limits = self.options.limits;
+        let archive_limits = FetchLimits {
+            max_asset_bytes: limits.max_level_bytes,
+            ..limits
+        };
+        let data = fetch(url, &archive_limits, None)
+            .map_err(|err| MirrorError::Fetch(url.to_string(), err))?;
+        let bundle = FetchBundle::from_bytes(url, &data, &limits)
+            .map_err(|err| MirrorError::Fetch(url.to_string(), err))?;
+        let manifest = LevelManifest::load_bundle(&bundle, false)
+            .map_err(|err| MirrorError::Manifest(url.to_string(), err))?;
+
+        for (_, portal) in manifest.portal_iter() {
+            if let Ok(link) = url.join(portal.link_href()) {
+                self.link_mirror(url, &link, depth);
+            }
+        }
+        return self.write(url, &data);
+    }
+
+    fn manifest_mirror(&mut self, url: &Url, depth: usize) -> Result<(), MirrorError> {
+        let data = fetch(url, &self.options.limits, None)
+            .map_err(|err| MirrorError::Fetch(url.to_string(), err))?;
+        let manifest = LevelManifest::from_bytes(&data, false).map_err(|err| {
+            MirrorError::Manifest(url.to_string(), LevelManifestLoadError::FromBytes(err))
+        })?;
+        // Older schemas are written out upgraded, so the rewrites below only need to know
+        // the current one. Current manifests are edited as published, keeping keys the game
+        // does not model, such as `$schema`.
+        let current = serde_json::to_value(&manifest).unwrap();
+        let mut value = match serde_json::from_slice::<Value>(&data) {
+            Ok(value) if value.get("_version") == current.get("_version") => value,
+            _ => current,
+        };
+
+        if let Some(level) = value.get_mut("level") {
+            for key in LEVEL_ASSET_KEYS {
+                if let Some(href) = level.get_mut(key) {
+                    self.href_mirror(url, href)?;
+                }
+            }
+            if let Some(mesh) = level.pointer_mut("/collider/mesh") {
+                self.href_mirror(url, mesh)?;
+            }
+            if let Some(sky) = level.pointer_mut("/environment/sky") {
+                if let Some(image) = sky.get_mut("image") {
+                    self.href_mirror(url, image)?;
+                }
+                let faces = sky.get_mut("faces").and_then(Value::as_array_mut);
+                for face in faces.into_iter().flatten() {
+                    self.href_mirror(url, face)?;
+                }
+            }
+            self.surfaces_mirror(url, level.get_mut("surface"))?;
+            self.libraries_mirror(url, level.get_mut("surface_library"))?;
+        }
+
+        if let Some(thumbnail) = value.pointer_mut("/meta/thumbnail") {
+            self.href_mirror(url, thumbnail)?;
+        }
+
+        // Integrity keys name the same assets as the hrefs above, so they move with them.
+        if let Some(integrity) = value.get_mut("integrity").and_then(Value::as_object_mut) {
+            let from = mirror_segments(url);
+            *integrity = std::mem::take(integrity)
+                .into_iter()
+                .map(|(href, digest)| match url.join(&href) {
+                    Ok(asset) => (mirror_href(&from, &mirror_segments(&asset)), digest),
+                    Err(_) => (href, digest),
+                })
+                .collect();
+        }
+
+        for (name, portal) in manifest.portal_iter() {
+            let entry = &mut value["portal"][name.as_str()];
+            self.href_mirror(url, &mut entry["mesh"])?;
+            if let Ok(link) = url.join(portal.link_href()) {
+                let href = self.link_mirror(url, &link, depth);
+                value["portal"][name.as_str()]["link"] = Value::String(href);
+            }
+        }
+
+        return self.write(url, &serde_json::to_vec_pretty(&value).unwrap());
+    }
+
+    // Mirrors every reachable level, carrying on past failures so one broken hall does
+    // not stop the rest of the graph.
+    pub fn run(&mut self) -> Vec<MirrorError> {
+        let mut errors = Vec::new();
+        while let Some((url, depth)) = self.queue.pop_front() {
+            let result = if FetchBundle::is_bundle(&url) {
+                self.bundle_mirror(&url, depth)
+            } else {
+                self.manifest_mirror(&url, depth)
+            };
+            match result {
+                Ok(()) => println!("mirrored {url}"),
+                Err(err) => errors.push(err),
+            }
+        }
+        return errors;
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use url::Url;
+
+use super::mirror::{Mirror, MirrorError, MirrorOptions};
+use super::stand_in::{stand_in_serve, StandIn};
+use halls::level::fetch::FetchLimits;
+
+fn manifest_json(mesh: &str, link: Option<&str>) -> Vec<u8> {
+    let portal = match link {
+        Some(link) => format!(r#"{{ "door": {{ "mesh": "door.glb", "link": "{link}" }} }}"#),
+        None => "{}".to_string(),
+    };
+    return format!(
+        r#"{{
+            "_version": "coco",
+            "meta": {{ "name": "Hall" }},
+            "level": {{
+                "mesh": "{mesh}",
+                "collider": {{ "mesh": "/shared/collider.glb" }},
+                "surface": {{
+                    "Wall": {{ "type": "TextureSingle", "frame": "/shared/wall.png" }}
+                }}
+            }},
+            "portal": {portal}
+        }}"#
+    )
+    .into_bytes();
+}
+
+fn stand_in_halls() -> StandIn {
+    return stand_in_serve(HashMap::from([
+        (
+            "a/manifest.json",
+            manifest_json("mesh.glb", Some("../b/manifest.json#door")),
+        ),
+        ("a/mesh.glb", b"mesh a".to_vec()),
+        ("a/door.glb", b"door a".to_vec()),
+        ("b/manifest.json", manifest_json("mesh.glb", None)),
+        ("b/mesh.glb", b"mesh b".to_vec()),
+        ("shared/wall.png", b"wall".to_vec()),
+        ("shared/collider.glb", b"collider".to_vec()),
+    ]));
+}
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("halls-mirror-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    return dir;
+}
+
+fn mirror_run(root: &Url, out_dir: &Path, max_depth: usize, allow: Vec<String>) -> Mirror {
+    let options = MirrorOptions {
+        max_depth,
+        allow,
+        limits: FetchLimits::default(),
+    };
+    let mut mirror = Mirror::new(root.clone(), out_dir.to_path_buf(), options);
+    let errors = mirror.run();
+    assert!(errors.is_empty(), "{errors:?}");
+    return mirror;
+}
+
+fn host_port(url: &Url) -> String {
+    return format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+}
+
+fn manifest_read(mirror: &Mirror, url: &Url) -> Value {
+    return serde_json::from_slice(&fs::read(mirror.path(url).unwrap()).unwrap()).unwrap();
+}
+
+#[test]
+fn test_mirror_rewrites_links_to_relative_paths() {
+    let stand_in = stand_in_halls();
+    let root = stand_in.url.join("a/manifest.json").unwrap();
+    let out_dir = out_dir("relative");
+
+    let mirror = mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+
+    let manifest = manifest_read(&mirror, &root);
+    assert_eq!(manifest["level"]["mesh"], "mesh.glb");
+    assert_eq!(
+        manifest["level"]["surface"]["Wall"]["frame"],
+        "../shared/wall.png"
+    );
+    assert_eq!(
+        manifest["level"]["collider"]["mesh"],
+        "../shared/collider.glb"
+    );
+    assert_eq!(
+        manifest["portal"]["door"]["link"],
+        "../b/manifest.json#door"
+    );
+
+    let b = root.join("../b/mesh.glb").unwrap();
+    assert_eq!(fs::read(mirror.path(&b).unwrap()).unwrap(), b"mesh b");
+    let wall = root.join("../shared/wall.png").unwrap();
+    assert_eq!(fs::read(mirror.path(&wall).unwrap()).unwrap(), b"wall");
+}
+
+#[test]
+fn test_mirror_depth_keeps_distant_links_absolute() {
+    let stand_in = stand_in_halls();
+    let root = stand_in.url.join("a/manifest.json").unwrap();
+    let out_dir = out_dir("depth");
+
+    let mirror = mirror_run(&root, &out_dir, 0, vec![host_port(&root)]);
+
+    let manifest = manifest_read(&mirror, &root);
+    let link = root.join("../b/manifest.json#door").unwrap();
+    assert_eq!(manifest["portal"]["door"]["link"], link.as_str());
+    let requests = stand_in.requests.lock().unwrap();
+    assert!(!requests.iter().any(|path| path.starts_with("b/")));
+}
+
+#[test]
+fn test_mirror_allowlist_keeps_foreign_links_absolute() {
+    let stand_in = stand_in_halls();
+    let root = stand_in.url.join("a/manifest.json").unwrap();
+    let out_dir = out_dir("allow");
+
+    let mirror = mirror_run(&root, &out_dir, usize::MAX, vec!["example.com".to_string()]);
+
+    let manifest = manifest_read(&mirror, &root);
+    let link = root.join("../b/manifest.json#door").unwrap();
+    assert_eq!(manifest["portal"]["door"]["link"], link.as_str());
+    let requests = stand_in.requests.lock().unwrap();
+    assert!(!requests.iter().any(|path| path.starts_with("b/")));
+}
+
+#[test]
+fn test_mirror_resume_skips_downloaded_assets() {
+    let stand_in = stand_in_halls();
+    let root = stand_in.url.join("a/manifest.json").unwrap();
+    let out_dir = out_dir("resume");
+
+    mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+    stand_in.requests.lock().unwrap().clear();
+    mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+
+    let requests = stand_in.requests.lock().unwrap();
+    assert!(requests.iter().all(|path| path.ends_with("manifest.json")));
+}
+
+#[test]
+fn test_mirror_writes_unversioned_manifest_upgraded() {
+    let manifest = br#"{
+        "meta": { "name": "Old Hall" },
+        "level": {
+            "model": "mesh.glb",
+            "surface": { "Wall": { "image": "../shared/wall.png", "collider_type": "wall" } }
+        },
+        "portal": {}
+    }"#;
+    let stand_in = stand_in_serve(HashMap::from([
+        ("old/manifest.json", manifest.to_vec()),
+        ("old/mesh.glb", b"mesh old".to_vec()),
+        ("shared/wall.png", b"wall".to_vec()),
+    ]));
+    let root = stand_in.url.join("old/manifest.json").unwrap();
+    let out_dir = out_dir("upgrade");
+
+    let mirror = mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+
+    let manifest = manifest_read(&mirror, &root);
+    assert_eq!(manifest["_version"], "coco");
+    assert_eq!(manifest["level"]["mesh"], "mesh.glb");
+    assert_eq!(
+        manifest["level"]["surface"]["Wall"]["frame"],
+        "../shared/wall.png"
+    );
+    let wall = root.join("../shared/wall.png").unwrap();
+    assert_eq!(fs::read(mirror.path(&wall).unwrap()).unwrap(), b"wall");
+}
+
+#[test]
+fn test_mirror_keeps_unmodelled_keys_of_current_manifests() {
+    let manifest = br#"{
+        "$schema": "https://example.com/manifest.schema.json",
+        "_version": "coco",
+        "meta": { "name": "Schema Hall" },
+        "level": { "mesh": "mesh.glb", "surface": {} },
+        "portal": {}
+    }"#;
+    let stand_in = stand_in_serve(HashMap::from([
+        ("schema/manifest.json", manifest.to_vec()),
+        ("schema/mesh.glb", b"mesh schema".to_vec()),
+    ]));
+    let root = stand_in.url.join("schema/manifest.json").unwrap();
+    let out_dir = out_dir("schema");
+
+    let mirror = mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+
+    let manifest = manifest_read(&mirror, &root);
+    assert_eq!(
+        manifest["$schema"],
+        "https://example.com/manifest.schema.json"
+    );
+    assert_eq!(manifest["level"]["mesh"], "mesh.glb");
+}
+
+#[test]
+fn test_mirror_copies_surface_libraries_with_rewritten_hrefs() {
+    let manifest = br#"{
+        "_version": "coco",
+        "meta": { "name": "Library Hall" },
+        "level": {
+            "mesh": "mesh.glb",
+            "surface_library": ["/shared/surfaces.json"],
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+    let library = br#"{
+        "surface_library": ["trim/trim.json", "../shared/surfaces.json"],
+        "surface": { "Brick": { "type": "TextureSingle", "frame": "/textures/brick.png" } }
+    }"#;
+    let trim = br#"{
+        "surface": { "Trim": { "type": "TextureMulti", "frames": ["trim.png"], "animation_speed": 1.0 } }
+    }"#;
+    let stand_in = stand_in_serve(HashMap::from([
+        ("lib/manifest.json", manifest.to_vec()),
+        ("lib/mesh.glb", b"mesh lib".to_vec()),
+        ("shared/surfaces.json", library.to_vec()),
+        ("shared/trim/trim.json", trim.to_vec()),
+        ("shared/trim/trim.png", b"trim".to_vec()),
+        ("textures/brick.png", b"brick".to_vec()),
+    ]));
+    let root = stand_in.url.join("lib/manifest.json").unwrap();
+    let out_dir = out_dir("library");
+
+    let mirror = mirror_run(&root, &out_dir, usize::MAX, vec![host_port(&root)]);
+
+    let manifest = manifest_read(&mirror, &root);
+    assert_eq!(
+        manifest["level"]["surface_library"][0],
+        "../shared/surfaces.json"
+    );
+    let library_url = root.join("/shared/surfaces.json").unwrap();
+    let library = manifest_read(&mirror, &library_url);
+    assert_eq!(library["surface_library"][0], "trim/trim.json");
+    assert_eq!(library["surface_library"][1], "surfaces.json");
+    assert_eq!(
+        library["surface"]["Brick"]["frame"],
+        "../textures/brick.png"
+    );
+    let trim = manifest_read(&mirror, &library_url.join("trim/trim.json").unwrap());
+    assert_eq!(trim["surface"]["Trim"]["frames"][0], "trim.png");
+    let brick = root.join("/textures/brick.png").unwrap();
+    assert_eq!(fs::read(mirror.path(&brick).unwrap()).unwrap(), b"brick");
+}
+
+#[test]
+fn test_mirror_refuses_segments_that_escape_out_dir() {
+    let manifest = br#"{
+        "_version": "coco",
+        "meta": { "name": "Hall" },
+        "level": { "mesh": "..%2F..%2Fescaped.glb", "surface": {} },
+        "portal": {}
+    }"#;
+    let stand_in = stand_in_serve(HashMap::from([
+        ("evil/manifest.json", manifest.to_vec()),
+        ("evil/..%2F..%2Fescaped.glb", b"escaped".to_vec()),
+    ]));
+    let root = stand_in.url.join("evil/manifest.json").unwrap();
+    let out_dir = out_dir("traversal");
+
+    let options = MirrorOptions {
+        max_depth: usize::MAX,
+        allow: vec![host_port(&root)],
+        limits: FetchLimits::default(),
+    };
+    let mut mirror = Mirror::new(root.clone(), out_dir.clone(), options);
+    let errors = mirror.run();
+
+    assert!(
+        matches!(errors.as_slice(), [MirrorError::Path(_)]),
+        "{errors:?}"
+    );
+    let mesh = root.join("..%2F..%2Fescaped.glb").unwrap();
+    assert!(mirror.path(&mesh).is_err());
+    assert!(!out_dir.join("escaped.glb").exists());
+}
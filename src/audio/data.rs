@@ -36,6 +36,10 @@ impl TrackData {
         return self.sample_rate;
     }
 
+    pub fn size_bytes(&self) -> u64 {
+        return std::mem::size_of_val(&*self.samples) as u64;
+    }
+
     pub(crate) fn samples(&self) -> Arc<[f32]> {
         return Arc::clone(&self.samples);
     }
@@ -1,9 +1,9 @@
 use bytemuck::{Pod, Zeroable};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable, Deserialize)]
-#[serde(from = "[u8; 4]")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable, Deserialize, Serialize)]
+#[serde(from = "[u8; 4]", into = "[u8; 4]")]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -29,6 +29,12 @@ impl From<[u8; 4]> for Color {
     }
 }
 
+impl From<Color> for [u8; 4] {
+    fn from(value: Color) -> Self {
+        return [value.r, value.g, value.b, value.a];
+    }
+}
+
 impl std::ops::Mul for Color {
     type Output = Color;
 
@@ -1,15 +1,18 @@
 use std::fs;
 use std::path::PathBuf;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use strum::{EnumCount, IntoEnumIterator};
 use url::Url;
 use winit::keyboard::Key;
 
 use super::ConfigControl;
+use crate::level::{FetchLimits, LevelPolicy};
 
 const CONFIG_PATH: &str = "halls/config.json";
 const DEFAULT_URL: &str = "https://lonnycorp.github.io/halls-nexus";
+const DEFAULT_PREFETCH_DEPTH: usize = 1;
+const DEFAULT_CACHE_BUDGET_BYTES: u64 = 512 * 1024 * 1024;
 
 fn config_path() -> PathBuf {
     let dir = std::env::var("XDG_STATE_HOME")
@@ -21,11 +24,53 @@ fn config_path() -> PathBuf {
     return dir.join(CONFIG_PATH);
 }
 
+// Configs saved before a control existed keep their bindings and take the default for
+// the new one.
+fn controls_deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<[Key; ConfigControl::COUNT], D::Error> {
+    let keys: Vec<Key> = Vec::deserialize(deserializer)?;
+    let controls: Vec<Key> = ConfigControl::iter()
+        .enumerate()
+        .map(|(ix, control)| {
+            keys.get(ix)
+                .cloned()
+                .unwrap_or_else(|| control.key_default())
+        })
+        .collect();
+    return Ok(controls.try_into().unwrap());
+}
+
+fn prefetch_depth_default() -> usize {
+    return DEFAULT_PREFETCH_DEPTH;
+}
+
+fn cache_budget_bytes_default() -> u64 {
+    return DEFAULT_CACHE_BUDGET_BYTES;
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     pub volume: f32,
     pub mouse_sensitivity: f32,
     pub default_url: Url,
+    #[serde(default)]
+    pub fetch_limits: FetchLimits,
+    #[serde(default = "prefetch_depth_default")]
+    pub prefetch_depth: usize,
+    #[serde(default = "cache_budget_bytes_default")]
+    pub cache_budget_bytes: u64,
+    #[serde(default)]
+    pub policy: LevelPolicy,
+    // Refuse manifests with unknown keys instead of loading them with warnings.
+    #[serde(default)]
+    pub strict_manifests: bool,
+    #[serde(default)]
+    pub watch: bool,
+    // Show authoring aids, such as the current level's warnings, over gameplay.
+    #[serde(default)]
+    pub dev_overlay: bool,
+    #[serde(deserialize_with = "controls_deserialize")]
     controls: [Key; ConfigControl::COUNT],
 }
 
@@ -56,6 +101,13 @@ impl Config {
             volume: 1.0,
             mouse_sensitivity: 1.0,
             default_url: Url::parse(DEFAULT_URL).unwrap(),
+            fetch_limits: FetchLimits::default(),
+            prefetch_depth: DEFAULT_PREFETCH_DEPTH,
+            cache_budget_bytes: DEFAULT_CACHE_BUDGET_BYTES,
+            policy: LevelPolicy::default(),
+            strict_manifests: false,
+            watch: false,
+            dev_overlay: false,
             controls,
         };
     }
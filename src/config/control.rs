@@ -8,6 +8,7 @@ pub enum ConfigControl {
     StrafeRight,
     Jump,
     Crouch,
+    Reload,
 }
 
 impl ConfigControl {
@@ -19,6 +20,7 @@ impl ConfigControl {
             ConfigControl::StrafeRight => Key::Character("d".into()),
             ConfigControl::Jump => Key::Named(NamedKey::Space),
             ConfigControl::Crouch => Key::Named(NamedKey::Control),
+            ConfigControl::Reload => Key::Character("r".into()),
         };
     }
 }
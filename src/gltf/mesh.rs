@@ -15,7 +15,7 @@ pub struct GLTFMesh {
 
 #[derive(Debug)]
 pub enum GLTFMeshError {
-    GLTF,
+    GLTF(::gltf::Error),
     NoScene,
     MultipleScenes,
     InconsistentDiffuseUVs,
@@ -23,6 +23,34 @@ pub enum GLTFMeshError {
     InconsistentColors,
 }
 
+impl std::fmt::Display for GLTFMeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            GLTFMeshError::GLTF(_) => write!(f, "invalid glTF data"),
+            GLTFMeshError::NoScene => write!(f, "glTF contains no scene"),
+            GLTFMeshError::MultipleScenes => write!(f, "glTF contains more than one scene"),
+            GLTFMeshError::InconsistentDiffuseUVs => {
+                write!(f, "some primitives are missing diffuse UVs")
+            }
+            GLTFMeshError::InconsistentLightmapUVs => {
+                write!(f, "some primitives are missing lightmap UVs")
+            }
+            GLTFMeshError::InconsistentColors => {
+                write!(f, "some primitives are missing vertex colors")
+            }
+        };
+    }
+}
+
+impl std::error::Error for GLTFMeshError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            GLTFMeshError::GLTF(err) => Some(err),
+            _ => None,
+        };
+    }
+}
+
 fn node_process_recursive(
     node: &::gltf::Node,
     buffers: &[::gltf::buffer::Data],
@@ -101,7 +129,7 @@ fn node_process_recursive(
 
 impl GLTFMesh {
     pub fn from_bytes(data: &[u8]) -> Result<Self, GLTFMeshError> {
-        let (document, buffers, _) = ::gltf::import_slice(data).map_err(|_| GLTFMeshError::GLTF)?;
+        let (document, buffers, _) = ::gltf::import_slice(data).map_err(GLTFMeshError::GLTF)?;
 
         let scenes: Vec<_> = document.scenes().collect();
         let scene = match scenes.len() {
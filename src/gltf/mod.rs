@@ -4,7 +4,5 @@ mod vertex;
 #[cfg(test)]
 mod test;
 
-pub use mesh::GLTFMesh;
-#[cfg(test)]
-pub use mesh::GLTFMeshError;
+pub use mesh::{GLTFMesh, GLTFMeshError};
 pub use vertex::GLTFVertex;
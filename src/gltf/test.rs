@@ -4,7 +4,7 @@ use crate::color::Color;
 #[test]
 fn test_invalid_data_returns_load_error() {
     let result = GLTFMesh::from_bytes(&[]);
-    assert!(matches!(result, Err(GLTFMeshError::GLTF)));
+    assert!(matches!(result, Err(GLTFMeshError::GLTF(_))));
 }
 
 #[test]
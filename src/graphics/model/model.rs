@@ -10,6 +10,18 @@ pub enum ModelUploadError {
     VerticesExceedCapacity,
 }
 
+impl std::fmt::Display for ModelUploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            ModelUploadError::VerticesExceedCapacity => {
+                write!(f, "vertex count exceeds buffer capacity")
+            }
+        };
+    }
+}
+
+impl std::error::Error for ModelUploadError {}
+
 pub struct Model {
     vertex_buffer: wgpu::Buffer,
     vertex_count: u32,
@@ -45,6 +57,10 @@ impl Model {
         return Ok(());
     }
 
+    pub fn size_bytes(&self) -> u64 {
+        return self.vertex_buffer.size();
+    }
+
     pub fn draw<'a>(&'a self, rp: &mut wgpu::RenderPass<'a>) {
         rp.set_vertex_buffer(MODEL_VERTEX_BUFFER_SLOT, self.vertex_buffer.slice(..));
         rp.draw(
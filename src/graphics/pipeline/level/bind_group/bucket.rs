@@ -0,0 +1,69 @@
+// Texture sizes a level may use, each backed by a fixed-size texture array.
+#[derive(Copy, Clone)]
+pub struct TextureBucket {
+    pub width: u32,
+    pub height: u32,
+    pub layers: usize,
+}
+
+pub const TEXTURE_BUCKETS: [TextureBucket; 6] = [
+    TextureBucket {
+        width: 0x800,
+        height: 0x800,
+        layers: 0x1,
+    },
+    TextureBucket {
+        width: 0x400,
+        height: 0x400,
+        layers: 0x4,
+    },
+    TextureBucket {
+        width: 0x200,
+        height: 0x200,
+        layers: 0x8,
+    },
+    TextureBucket {
+        width: 0x100,
+        height: 0x100,
+        layers: 0x20,
+    },
+    TextureBucket {
+        width: 0x80,
+        height: 0x80,
+        layers: 0x40,
+    },
+    TextureBucket {
+        width: 0x40,
+        height: 0x40,
+        layers: 0x100,
+    },
+];
+
+// The smallest bucket with at least as many texels as a `w` x `h` image, or the largest
+// bucket for bigger images. Fitting by area rather than by side keeps long, thin images
+// such as a 2048x16 trim out of the single 2048 layer. Images that are not exactly a
+// bucket's size are resampled.
+pub fn texture_bucket_fit(w: u32, h: u32) -> usize {
+    let texels = w as u64 * h as u64;
+    return TEXTURE_BUCKETS
+        .iter()
+        .rposition(|bucket| bucket.width as u64 * bucket.height as u64 >= texels)
+        .unwrap_or(0);
+}
+
+// Takes the next free layer of bucket `ix`, or of a larger bucket once it is full. Every
+// array is allocated up front, so spilling over costs no memory. Returns the bucket and
+// layer, or `None` when `ix` and every larger bucket are full.
+pub fn texture_bucket_alloc(
+    next_free: &mut [usize; TEXTURE_BUCKETS.len()],
+    ix: usize,
+) -> Option<(usize, usize)> {
+    for bucket_ix in (0..=ix).rev() {
+        let layer = next_free[bucket_ix];
+        if layer < TEXTURE_BUCKETS[bucket_ix].layers {
+            next_free[bucket_ix] += 1;
+            return Some((bucket_ix, layer));
+        }
+    }
+    return None;
+}
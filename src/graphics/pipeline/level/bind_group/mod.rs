@@ -1,8 +1,13 @@
+mod bucket;
 mod config;
 mod texture;
 
+#[cfg(test)]
+mod test;
+
 pub use config::config_bind_group_layout_create;
 pub use texture::texture_bind_group_layout_create;
 
+pub use bucket::{texture_bucket_alloc, texture_bucket_fit, TEXTURE_BUCKETS};
 pub use config::PipelineLevelBindGroupConfig;
-pub use texture::{PipelineLevelBindGroupTexture, TEXTURE_BUCKETS};
+pub use texture::PipelineLevelBindGroupTexture;
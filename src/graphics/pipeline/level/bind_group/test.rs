@@ -0,0 +1,55 @@
+use super::*;
+
+fn bucket_ix(width: u32) -> usize {
+    return TEXTURE_BUCKETS
+        .iter()
+        .position(|bucket| bucket.width == width)
+        .unwrap();
+}
+
+#[test]
+fn test_texture_bucket_fit_takes_smallest_bucket_by_area() {
+    assert_eq!(texture_bucket_fit(64, 64), bucket_ix(64));
+    assert_eq!(texture_bucket_fit(1, 1), bucket_ix(64));
+    assert_eq!(texture_bucket_fit(96, 96), bucket_ix(128));
+    assert_eq!(texture_bucket_fit(256, 128), bucket_ix(256));
+    assert_eq!(texture_bucket_fit(2048, 2048), bucket_ix(2048));
+    assert_eq!(texture_bucket_fit(4096, 4096), bucket_ix(2048));
+}
+
+#[test]
+fn test_texture_bucket_fit_keeps_thin_images_out_of_large_buckets() {
+    assert_eq!(texture_bucket_fit(2048, 16), bucket_ix(256));
+    assert_eq!(texture_bucket_fit(16, 2048), bucket_ix(256));
+    assert_eq!(texture_bucket_fit(1024, 4), bucket_ix(64));
+}
+
+#[test]
+fn test_texture_bucket_alloc_spills_into_larger_buckets() {
+    let mut next_free = [0; TEXTURE_BUCKETS.len()];
+    let ix = bucket_ix(1024);
+    for layer in 0..TEXTURE_BUCKETS[ix].layers {
+        assert_eq!(texture_bucket_alloc(&mut next_free, ix), Some((ix, layer)));
+    }
+    assert_eq!(
+        texture_bucket_alloc(&mut next_free, ix),
+        Some((bucket_ix(2048), 0))
+    );
+}
+
+#[test]
+fn test_texture_bucket_alloc_fails_when_every_larger_slot_is_taken() {
+    let mut next_free = [0; TEXTURE_BUCKETS.len()];
+    let ix = bucket_ix(2048);
+    assert_eq!(texture_bucket_alloc(&mut next_free, ix), Some((ix, 0)));
+    assert_eq!(texture_bucket_alloc(&mut next_free, ix), None);
+
+    // Smaller buckets still have their own slots, but cannot spill into the full one.
+    let ix = bucket_ix(1024);
+    next_free[ix] = TEXTURE_BUCKETS[ix].layers;
+    assert_eq!(texture_bucket_alloc(&mut next_free, ix), None);
+    assert_eq!(
+        texture_bucket_alloc(&mut next_free, bucket_ix(512)),
+        Some((bucket_ix(512), 0))
+    );
+}
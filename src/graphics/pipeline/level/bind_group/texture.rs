@@ -3,47 +3,9 @@ use crate::graphics::texture::{
     texture_array_binding_array_bind_group_layout_entry, Sampler, TextureArray,
 };
 
-const BIND_GROUP_INDEX: u32 = 0;
-
-#[derive(Copy, Clone)]
-pub struct TextureBucket {
-    pub width: u32,
-    pub height: u32,
-    pub layers: usize,
-}
+use super::bucket::TEXTURE_BUCKETS;
 
-pub const TEXTURE_BUCKETS: [TextureBucket; 6] = [
-    TextureBucket {
-        width: 0x800,
-        height: 0x800,
-        layers: 0x1,
-    },
-    TextureBucket {
-        width: 0x400,
-        height: 0x400,
-        layers: 0x4,
-    },
-    TextureBucket {
-        width: 0x200,
-        height: 0x200,
-        layers: 0x8,
-    },
-    TextureBucket {
-        width: 0x100,
-        height: 0x100,
-        layers: 0x20,
-    },
-    TextureBucket {
-        width: 0x80,
-        height: 0x80,
-        layers: 0x40,
-    },
-    TextureBucket {
-        width: 0x40,
-        height: 0x40,
-        layers: 0x100,
-    },
-];
+const BIND_GROUP_INDEX: u32 = 0;
 
 pub fn texture_bind_group_layout_create(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     return device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -1,21 +1,54 @@
 use bytemuck::{Pod, Zeroable};
 
+#[derive(Copy, Clone, Default)]
+pub enum PipelineLevelFogMode {
+    #[default]
+    None,
+    Linear,
+    Exponential,
+}
+
+// Distance fog for the level being drawn. `color` is linear RGBA, with alpha capping how
+// much fog is applied.
+#[derive(Copy, Clone, Default)]
+pub struct PipelineLevelFog {
+    pub mode: PipelineLevelFogMode,
+    pub color: [f32; 4],
+    pub start: f32,
+    pub density: f32,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct LevelPushConstants {
     pub clock: u32,
     pub lightmap_material_id: u32,
+    pub fog_mode: u32,
+    pub fog_start: f32,
+    pub fog_density: f32,
+    pub _padding: [u32; 3],
+    pub fog_color: [f32; 4],
 }
 
 pub const PUSH_CONSTANT_RANGE: wgpu::PushConstantRange = wgpu::PushConstantRange {
     stages: wgpu::ShaderStages::FRAGMENT,
-    range: 0..8,
+    range: 0..48,
 };
 
-pub fn bind(rp: &mut wgpu::RenderPass, clock: u32, lightmap_material_id: u32) {
+pub fn bind(
+    rp: &mut wgpu::RenderPass,
+    clock: u32,
+    lightmap_material_id: u32,
+    fog: PipelineLevelFog,
+) {
     let pc = LevelPushConstants {
         clock,
         lightmap_material_id,
+        fog_mode: fog.mode as u32,
+        fog_start: fog.start,
+        fog_density: fog.density,
+        _padding: [0; 3],
+        fog_color: fog.color,
     };
     rp.set_push_constants(wgpu::ShaderStages::FRAGMENT, 0, bytemuck::bytes_of(&pc));
 }
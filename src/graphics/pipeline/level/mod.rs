@@ -3,7 +3,8 @@ mod constant;
 mod pipeline;
 
 pub use bind_group::{
-    PipelineLevelBindGroupConfig, PipelineLevelBindGroupTexture, TEXTURE_BUCKETS,
+    texture_bucket_alloc, texture_bucket_fit, PipelineLevelBindGroupConfig,
+    PipelineLevelBindGroupTexture, TEXTURE_BUCKETS,
 };
-pub use constant::bind as bind_level_constants;
+pub use constant::{bind as bind_level_constants, PipelineLevelFog, PipelineLevelFogMode};
 pub use pipeline::pipeline_level_create;
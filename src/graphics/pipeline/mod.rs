@@ -1,3 +1,4 @@
 pub mod level;
 pub mod overlay;
 pub mod portal;
+pub mod sky;
@@ -1,4 +1,6 @@
-use crate::graphics::sprite::{SYSTEM_TEXTURE_REF, TEXT_TEXTURE_REF};
+use image::RgbaImage;
+
+use crate::graphics::sprite::{SYSTEM_TEXTURE_REF, TEXT_TEXTURE_REF, THUMBNAIL_TEXTURE_REF};
 use crate::graphics::texture::{
     sampler_bind_group_layout_entry, texture_array_bind_group_layout_entry, Sampler, TextureArray,
 };
@@ -8,7 +10,7 @@ const BIND_GROUP_INDEX: u32 = 0;
 const TEXT_TEXTURE_PATH: &str = "texture/text.png";
 const SYSTEM_TEXTURE_PATH: &str = "texture/system.png";
 const OVERLAY_TEXTURE_SIZE: (u32, u32) = (512, 512);
-const OVERLAY_TEXTURE_LAYERS: usize = 2;
+const OVERLAY_TEXTURE_LAYERS: usize = 3;
 
 pub fn texture_bind_group_layout_create(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     return device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -21,6 +23,7 @@ pub fn texture_bind_group_layout_create(device: &wgpu::Device) -> wgpu::BindGrou
 }
 
 pub struct PipelineOverlayBindGroupTexture {
+    diffuse: TextureArray,
     bind_group: wgpu::BindGroup,
 }
 
@@ -60,7 +63,20 @@ impl PipelineOverlayBindGroupTexture {
             ],
         });
 
-        return Self { bind_group };
+        return Self {
+            diffuse,
+            bind_group,
+        };
+    }
+
+    // Replaces the thumbnail layer, placing `thumbnail` in its top-left corner.
+    pub fn thumbnail_write(&self, queue: &wgpu::Queue, thumbnail: &RgbaImage) {
+        let (width, height) = OVERLAY_TEXTURE_SIZE;
+        let mut layer = RgbaImage::new(width, height);
+        image::imageops::replace(&mut layer, thumbnail, 0, 0);
+        self.diffuse
+            .write(queue, THUMBNAIL_TEXTURE_REF.layer as usize, &layer)
+            .unwrap();
     }
 
     pub fn bind<'a>(&'a self, rp: &mut wgpu::RenderPass<'a>) {
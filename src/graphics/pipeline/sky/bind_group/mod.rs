@@ -0,0 +1,5 @@
+mod texture;
+
+pub use texture::texture_bind_group_layout_create;
+
+pub use texture::PipelineSkyBindGroupTexture;
@@ -0,0 +1,51 @@
+use crate::graphics::texture::{
+    sampler_bind_group_layout_entry, texture_cube_bind_group_layout_entry, Sampler, TextureCube,
+};
+
+const BIND_GROUP_INDEX: u32 = 0;
+
+pub fn texture_bind_group_layout_create(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    return device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Sky Texture Bind Group Layout"),
+        entries: &[
+            sampler_bind_group_layout_entry(0),
+            texture_cube_bind_group_layout_entry(1),
+        ],
+    });
+}
+
+pub struct PipelineSkyBindGroupTexture {
+    bind_group: wgpu::BindGroup,
+}
+
+impl PipelineSkyBindGroupTexture {
+    pub fn new(device: &wgpu::Device, sky: &TextureCube) -> Self {
+        let layout = texture_bind_group_layout_create(device);
+        let sampler = Sampler::new(
+            device,
+            (
+                wgpu::AddressMode::ClampToEdge,
+                wgpu::AddressMode::ClampToEdge,
+            ),
+            wgpu::FilterMode::Linear,
+        );
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Sky Texture Bind Group"),
+            layout: &layout,
+            entries: &[
+                sampler.bind_group_entry(0),
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(sky.view()),
+                },
+            ],
+        });
+
+        return Self { bind_group };
+    }
+
+    pub fn bind<'a>(&'a self, rp: &mut wgpu::RenderPass<'a>) {
+        rp.set_bind_group(BIND_GROUP_INDEX, &self.bind_group, &[]);
+    }
+}
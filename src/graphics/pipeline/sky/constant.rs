@@ -0,0 +1,21 @@
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SkyPushConstants {
+    // Maps clip space back to world directions; built from the view without translation.
+    pub inverse_view_projection: Mat4,
+}
+
+pub const PUSH_CONSTANT_RANGE: wgpu::PushConstantRange = wgpu::PushConstantRange {
+    stages: wgpu::ShaderStages::FRAGMENT,
+    range: 0..64,
+};
+
+pub fn bind(rp: &mut wgpu::RenderPass, inverse_view_projection: Mat4) {
+    let pc = SkyPushConstants {
+        inverse_view_projection,
+    };
+    rp.set_push_constants(wgpu::ShaderStages::FRAGMENT, 0, bytemuck::bytes_of(&pc));
+}
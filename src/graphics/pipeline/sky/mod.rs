@@ -0,0 +1,7 @@
+pub mod bind_group;
+mod constant;
+mod pipeline;
+
+pub use bind_group::PipelineSkyBindGroupTexture;
+pub use constant::bind as bind_sky_constants;
+pub use pipeline::pipeline_sky_create;
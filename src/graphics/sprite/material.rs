@@ -10,9 +10,15 @@ pub const SYSTEM_TEXTURE_REF: MaterialTextureRef = MaterialTextureRef {
     bucket: 0,
     layer: 1,
 };
+// Holds the thumbnail of whichever level the info panel last showed.
+pub const THUMBNAIL_TEXTURE_REF: MaterialTextureRef = MaterialTextureRef {
+    bucket: 0,
+    layer: 2,
+};
 
 const TEXT_TEXTURE_REFS: [MaterialTextureRef; 1] = [TEXT_TEXTURE_REF];
 const SYSTEM_TEXTURE_REFS: [MaterialTextureRef; 1] = [SYSTEM_TEXTURE_REF];
+const THUMBNAIL_TEXTURE_REFS: [MaterialTextureRef; 1] = [THUMBNAIL_TEXTURE_REF];
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct SpriteMaterialIndexData<'a> {
@@ -32,6 +38,7 @@ pub enum SpriteMaterial {
     SystemGray,
     SystemCyan,
     SystemBlack,
+    Thumbnail,
 }
 
 impl SpriteMaterial {
@@ -85,6 +92,12 @@ impl SpriteMaterial {
                 texture_refs: &SYSTEM_TEXTURE_REFS,
                 color: Color::BLACK,
             },
+            SpriteMaterial::Thumbnail => SpriteMaterialIndexData {
+                material_ix: 8,
+                speed: STATIC_ANIMATION_SPEED,
+                texture_refs: &THUMBNAIL_TEXTURE_REFS,
+                color: Color::WHITE,
+            },
         };
     }
 }
@@ -1,5 +1,6 @@
 mod border;
 mod material;
+mod progress;
 mod solid;
 mod sprite;
 mod system;
@@ -7,12 +8,13 @@ mod text;
 mod vertex;
 
 pub use border::SpriteBorder;
-pub use material::{SpriteMaterial, SYSTEM_TEXTURE_REF, TEXT_TEXTURE_REF};
+pub use material::{SpriteMaterial, SYSTEM_TEXTURE_REF, TEXT_TEXTURE_REF, THUMBNAIL_TEXTURE_REF};
+pub use progress::SpriteProgress;
 pub use solid::SpriteSolid;
 pub use sprite::Sprite;
 pub use system::{Glyph, SpriteGlyph, SpriteLogo, SystemColor};
 pub use text::{
-    OptionState, SpriteLabel, SpriteLabelAlignment, SpriteText, SpriteTextInput, SpriteTextOption,
-    TextColor, TEXT_SIZE,
+    text_wrap, OptionState, SpriteLabel, SpriteLabelAlignment, SpriteText, SpriteTextInput,
+    SpriteTextOption, TextColor, TEXT_SIZE,
 };
 pub use vertex::SpriteVertex;
@@ -0,0 +1,29 @@
+use glam::Vec2;
+
+use super::{SpriteMaterial, SpriteSolid, SpriteVertex};
+
+pub struct SpriteProgress {
+    position: Vec2,
+    size: Vec2,
+    fraction: f32,
+}
+
+impl SpriteProgress {
+    pub fn new(position: Vec2, size: Vec2, fraction: f32) -> Self {
+        return Self {
+            position,
+            size,
+            fraction: fraction.clamp(0.0, 1.0),
+        };
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item = SpriteVertex> {
+        let track = SpriteSolid::new(self.position, self.size, SpriteMaterial::SystemGray);
+        let fill = SpriteSolid::new(
+            self.position,
+            Vec2::new(self.size.x * self.fraction, self.size.y),
+            SpriteMaterial::SystemCyan,
+        );
+        return track.vertices().chain(fill.vertices());
+    }
+}
@@ -3,9 +3,11 @@ mod input;
 mod label;
 mod option;
 mod text;
+mod wrap;
 
 pub use color::TextColor;
 pub use input::SpriteTextInput;
 pub use label::{SpriteLabel, SpriteLabelAlignment};
 pub use option::{OptionState, SpriteTextOption};
 pub use text::{SpriteText, TEXT_SIZE};
+pub use wrap::text_wrap;
@@ -0,0 +1,21 @@
+// Splits `text` into lines of at most `max_chars` bytes, breaking after the last space
+// that fits where there is one.
+pub fn text_wrap(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut rest = line;
+        while rest.len() > max_chars {
+            let mut split = max_chars;
+            while !rest.is_char_boundary(split) {
+                split -= 1;
+            }
+            if let Some(space) = rest[..split].rfind(' ').filter(|&space| space > 0) {
+                split = space + 1;
+            }
+            lines.push(&rest[..split]);
+            rest = &rest[split..];
+        }
+        lines.push(rest);
+    }
+    return lines;
+}
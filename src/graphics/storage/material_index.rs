@@ -14,6 +14,21 @@ pub enum MaterialIndexStorageBufferDataWriteError {
     TooManyFrames,
 }
 
+impl std::fmt::Display for MaterialIndexStorageBufferDataWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            MaterialIndexStorageBufferDataWriteError::TooManyMaterials => {
+                write!(f, "too many materials (max {})", MAX_MATERIAL_ID)
+            }
+            MaterialIndexStorageBufferDataWriteError::TooManyFrames => {
+                write!(f, "too many texture frames (max {})", MAX_FRAMES)
+            }
+        };
+    }
+}
+
+impl std::error::Error for MaterialIndexStorageBufferDataWriteError {}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct MaterialTextureRef {
@@ -92,6 +107,10 @@ impl MaterialIndexStorageBuffer {
         return Self { buffer };
     }
 
+    pub fn size_bytes(&self) -> u64 {
+        return self.buffer.size();
+    }
+
     pub fn write(&self, queue: &wgpu::Queue, data: &MaterialIndexStorageBufferData) {
         queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(data));
     }
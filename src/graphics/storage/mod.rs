@@ -1,5 +1,6 @@
 mod material_index;
 
 pub use material_index::{
-    MaterialIndexStorageBuffer, MaterialIndexStorageBufferData, MaterialTextureRef,
+    MaterialIndexStorageBuffer, MaterialIndexStorageBufferData,
+    MaterialIndexStorageBufferDataWriteError, MaterialTextureRef,
 };
@@ -52,6 +52,21 @@ pub enum TextureArrayWriteError {
     LayerOutOfBounds,
 }
 
+impl std::fmt::Display for TextureArrayWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            TextureArrayWriteError::DimensionsMismatch => {
+                write!(f, "image dimensions do not match texture array")
+            }
+            TextureArrayWriteError::LayerOutOfBounds => {
+                write!(f, "no free layers left for this texture size")
+            }
+        };
+    }
+}
+
+impl std::error::Error for TextureArrayWriteError {}
+
 impl TextureArray {
     pub fn new(device: &wgpu::Device, dims: (u32, u32), size: usize) -> Self {
         let (width, height) = dims;
@@ -90,6 +105,11 @@ impl TextureArray {
         return &self.view;
     }
 
+    pub fn size_bytes(&self) -> u64 {
+        let (width, height) = self.dims;
+        return 4 * width as u64 * height as u64 * self.layers as u64;
+    }
+
     pub fn write(
         &self,
         queue: &wgpu::Queue,
@@ -1,10 +1,12 @@
 mod array;
+mod cube;
 mod depth;
 mod sampler;
 
 pub use array::{
     texture_array_bind_group_layout_entry, texture_array_binding_array_bind_group_entry,
-    texture_array_binding_array_bind_group_layout_entry, TextureArray,
+    texture_array_binding_array_bind_group_layout_entry, TextureArray, TextureArrayWriteError,
 };
+pub use cube::{texture_cube_bind_group_layout_entry, TextureCube};
 pub use depth::TextureDepth;
 pub use sampler::{bind_group_layout_entry as sampler_bind_group_layout_entry, Sampler};
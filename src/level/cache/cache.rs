@@ -0,0 +1,457 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use url::Url;
+
+use super::loader::{LevelCacheLevel, LevelCacheLoader, LevelLoader};
+use crate::level::level::LevelLoadError;
+use crate::level::watch::{LevelWatch, LevelWatchSnapshot};
+use crate::level::{Level, LevelLoadCancel, LevelLoadProgress, LevelLoadProgressSnapshot};
+
+pub const MAX_LOADERS: usize = 4;
+const MAX_RETRIES: u32 = 5;
+pub const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+// Failures hold no level data but still take an entry each, so only the most recently
+// used are kept.
+pub const MAX_FAILED: usize = 16;
+
+struct LevelLoad<T> {
+    handle: JoinHandle<Result<Arc<T>, LevelLoadError>>,
+    cancel: LevelLoadCancel,
+    progress: LevelLoadProgress,
+    // Taken before the load starts, when watching.
+    snapshot: Option<LevelWatchSnapshot>,
+    // Reload requested while this load was running, so what it read may be stale.
+    dirty: bool,
+}
+
+struct LevelFailure {
+    error: Arc<LevelLoadError>,
+    retry_at: Option<Instant>,
+}
+
+enum LevelEntry<T> {
+    Queued,
+    // A ready level waiting for a loader to reload it.
+    ReloadQueued(Arc<T>),
+    Loading(LevelLoad<T>),
+    Reloading(Arc<T>, LevelLoad<T>),
+    Ready(Arc<T>),
+    Failed(LevelFailure),
+}
+
+impl<T> LevelEntry<T> {
+    fn level(&self) -> Option<&Arc<T>> {
+        return match self {
+            LevelEntry::Ready(level)
+            | LevelEntry::ReloadQueued(level)
+            | LevelEntry::Reloading(level, _) => Some(level),
+            _ => None,
+        };
+    }
+
+    fn load(&self) -> Option<&LevelLoad<T>> {
+        return match self {
+            LevelEntry::Loading(load) | LevelEntry::Reloading(_, load) => Some(load),
+            _ => None,
+        };
+    }
+
+    fn progress(&self) -> Option<LevelLoadProgressSnapshot> {
+        return match self {
+            LevelEntry::Queued => Some(LevelLoadProgressSnapshot::default()),
+            LevelEntry::Loading(load) => Some(load.progress.snapshot()),
+            _ => None,
+        };
+    }
+}
+
+pub enum LevelCacheResult<T = Level> {
+    Loading(LevelLoadProgressSnapshot),
+    Ready(Arc<T>),
+    Failed(Arc<LevelLoadError>),
+}
+
+pub struct LevelCache<L: LevelCacheLoader = LevelLoader> {
+    loader: Arc<L>,
+    budget_bytes: u64,
+    size_bytes: u64,
+    queued: VecDeque<Url>,
+    running: Vec<Url>,
+    prefetch: VecDeque<Url>,
+    prefetch_stalled: bool,
+    neighbourhood: Vec<Url>,
+    pinned: Vec<Url>,
+    retries: HashMap<Url, u32>,
+    watch: Option<LevelWatch>,
+    reload_errors: HashMap<Url, Arc<LevelLoadError>>,
+    cache: LruCache<Url, LevelEntry<L::Level>>,
+}
+
+impl<L: LevelCacheLoader> LevelCache<L> {
+    pub fn new(loader: L, budget_bytes: u64, watch: bool) -> Self {
+        Self {
+            loader: Arc::new(loader),
+            budget_bytes,
+            size_bytes: 0,
+            queued: VecDeque::new(),
+            running: Vec::new(),
+            prefetch: VecDeque::new(),
+            prefetch_stalled: false,
+            neighbourhood: Vec::new(),
+            pinned: Vec::new(),
+            retries: HashMap::new(),
+            watch: watch.then(LevelWatch::new),
+            reload_errors: HashMap::new(),
+            cache: LruCache::unbounded(),
+        }
+    }
+
+    pub fn get(&mut self, url: &Url) -> LevelCacheResult<L::Level> {
+        if self.cache.contains(url) {
+            return match self.cache.get(url).unwrap() {
+                LevelEntry::Ready(level)
+                | LevelEntry::ReloadQueued(level)
+                | LevelEntry::Reloading(level, _) => LevelCacheResult::Ready(Arc::clone(level)),
+                entry @ (LevelEntry::Queued | LevelEntry::Loading(_)) => {
+                    LevelCacheResult::Loading(entry.progress().unwrap())
+                }
+                LevelEntry::Failed(failure) => LevelCacheResult::Failed(Arc::clone(&failure.error)),
+            };
+        }
+
+        self.cache.put(url.clone(), LevelEntry::Queued);
+        self.queued.push_back(url.clone());
+        return LevelCacheResult::Loading(LevelLoadProgressSnapshot::default());
+    }
+
+    // Progress of a queued or first-time load of `url`, without queueing it or touching
+    // its recency.
+    pub fn progress(&self, url: &Url) -> Option<LevelLoadProgressSnapshot> {
+        return self.cache.peek(url).and_then(LevelEntry::progress);
+    }
+
+    // Abandons a queued or running load. A running loader notices between asset fetches,
+    // after which its entry is dropped so the next `get` starts afresh.
+    pub fn cancel(&mut self, url: &Url) {
+        match self.cache.peek(url) {
+            Some(LevelEntry::Queued) => {
+                self.cache.pop(url);
+                self.queued.retain(|queued| queued != url);
+            }
+            Some(LevelEntry::ReloadQueued(_)) => {
+                let entry = self.cache.peek_mut(url).unwrap();
+                let LevelEntry::ReloadQueued(level) = std::mem::replace(entry, LevelEntry::Queued)
+                else {
+                    unreachable!();
+                };
+                *entry = LevelEntry::Ready(level);
+                self.queued.retain(|queued| queued != url);
+            }
+            Some(LevelEntry::Loading(load)) | Some(LevelEntry::Reloading(_, load)) => {
+                load.cancel.cancel();
+            }
+            _ => {}
+        }
+    }
+
+    // Reloads `url` in place: a ready level keeps being served until its replacement
+    // loads, and a failed one is retried. Either waits for a free loader like any load.
+    pub fn reload(&mut self, url: &Url) {
+        match self.cache.peek(url) {
+            Some(LevelEntry::Ready(_)) => {
+                let entry = self.cache.peek_mut(url).unwrap();
+                let LevelEntry::Ready(level) = std::mem::replace(entry, LevelEntry::Queued) else {
+                    unreachable!();
+                };
+                *entry = LevelEntry::ReloadQueued(level);
+                self.queued.push_back(url.clone());
+            }
+            Some(LevelEntry::Failed(_)) => {
+                self.retries.remove(url);
+                self.cache.put(url.clone(), LevelEntry::Queued);
+                self.queued.push_back(url.clone());
+            }
+            Some(LevelEntry::Loading(_) | LevelEntry::Reloading(..)) => {
+                let entry = self.cache.peek_mut(url).unwrap();
+                if let LevelEntry::Loading(load) | LevelEntry::Reloading(_, load) = entry {
+                    load.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Why the last reload of `url` failed, while the previous version is still served.
+    pub fn reload_error(&self, url: &Url) -> Option<Arc<LevelLoadError>> {
+        return self.reload_errors.get(url).cloned();
+    }
+
+    fn spawn(&self, url: &Url) -> LevelLoad<L::Level> {
+        let loader = Arc::clone(&self.loader);
+        let url_clone = url.clone();
+        let cancel = LevelLoadCancel::default();
+        let cancel_clone = cancel.clone();
+        let progress = LevelLoadProgress::default();
+        let progress_clone = progress.clone();
+        let snapshot = self.watch.as_ref().map(|watch| watch.snapshot(url));
+        let handle = thread::spawn(move || {
+            loader
+                .load(url_clone, &cancel_clone, &progress_clone)
+                .map(Arc::new)
+        });
+        return LevelLoad {
+            handle,
+            cancel,
+            progress,
+            snapshot,
+            dirty: false,
+        };
+    }
+
+    fn load(&mut self, url: &Url) {
+        let load = self.spawn(url);
+        self.cache.put(url.clone(), LevelEntry::Loading(load));
+        self.running.push(url.clone());
+    }
+
+    fn reload_start(&mut self, url: &Url) {
+        let load = self.spawn(url);
+        let entry = self.cache.peek_mut(url).unwrap();
+        let LevelEntry::ReloadQueued(level) = std::mem::replace(entry, LevelEntry::Queued) else {
+            unreachable!();
+        };
+        *entry = LevelEntry::Reloading(level, load);
+        self.running.push(url.clone());
+    }
+
+    // Pins `url` and its direct portal destinations against eviction, and queues the
+    // levels reachable through at most `depth` portals for prefetch. Only ready levels
+    // are expanded, so the neighbourhood grows as prefetches land.
+    pub fn neighbourhood_update(&mut self, url: &Url, depth: usize) {
+        if self.neighbourhood.first() != Some(url) {
+            self.prefetch_stalled = false;
+        }
+        self.prefetch.clear();
+        self.neighbourhood.clear();
+        self.neighbourhood.push(url.clone());
+        self.pinned.clear();
+        self.pinned.push(url.clone());
+
+        let mut frontier = vec![url.clone()];
+        for ix in 0..depth.max(1) {
+            let mut next = Vec::new();
+            for level_url in frontier {
+                let Some(level) = self.cache.peek(&level_url).and_then(LevelEntry::level) else {
+                    continue;
+                };
+                for mut link in level.link_urls() {
+                    link.set_fragment(None);
+                    if ix == 0 && !self.pinned.contains(&link) {
+                        self.pinned.push(link.clone());
+                    }
+                    if ix >= depth || self.neighbourhood.contains(&link) {
+                        continue;
+                    }
+                    if !self.cache.contains(&link) {
+                        self.prefetch.push_back(link.clone());
+                    }
+                    self.neighbourhood.push(link.clone());
+                    next.push(link);
+                }
+            }
+            frontier = next;
+        }
+    }
+
+    // Least recently used ready level outside `keep`, other than `loaded`.
+    fn evict_candidate(&self, keep: &[Url], loaded: &Url) -> Option<Url> {
+        return self.cache.iter().rev().find_map(|(url, entry)| {
+            let evictable =
+                matches!(entry, LevelEntry::Ready(_)) && url != loaded && !keep.contains(url);
+            return evictable.then(|| url.clone());
+        });
+    }
+
+    fn evict(&mut self, loaded: &Url) {
+        while self.size_bytes > self.budget_bytes {
+            let url = match self.evict_candidate(&self.neighbourhood, loaded) {
+                Some(url) => url,
+                None => match self.evict_candidate(&self.pinned, loaded) {
+                    Some(url) => {
+                        // The neighbourhood no longer fits; stop prefetching until the
+                        // player moves rather than evicting and reloading in a loop.
+                        self.prefetch_stalled = true;
+                        url
+                    }
+                    None => return,
+                },
+            };
+            if let Some(LevelEntry::Ready(level)) = self.cache.pop(&url) {
+                self.size_bytes -= level.size_bytes();
+            }
+            if let Some(watch) = &mut self.watch {
+                watch.untrack(&url);
+            }
+            self.reload_errors.remove(&url);
+        }
+    }
+
+    // Drops the least recently used failures beyond `MAX_FAILED`, so the next `get` of
+    // one starts afresh.
+    fn failed_evict(&mut self) {
+        let failed: Vec<Url> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| matches!(entry, LevelEntry::Failed(_)))
+            .skip(MAX_FAILED)
+            .map(|(url, _)| url.clone())
+            .collect();
+        for url in failed {
+            self.cache.pop(&url);
+            self.retries.remove(&url);
+        }
+    }
+
+    fn prefetch_update(&mut self) {
+        if !self.queued.is_empty() || !self.running.is_empty() || self.prefetch_stalled {
+            return;
+        }
+        let Some(url) = self.prefetch.pop_front() else {
+            return;
+        };
+        if self.cache.contains(&url) {
+            return;
+        }
+        self.load(&url);
+    }
+
+    fn queued_update(&mut self) {
+        while self.running.len() < MAX_LOADERS {
+            let Some(url) = self.queued.pop_front() else {
+                return;
+            };
+            match self.cache.peek(&url) {
+                Some(LevelEntry::Queued) => self.load(&url),
+                Some(LevelEntry::ReloadQueued(_)) => self.reload_start(&url),
+                _ => {}
+            }
+        }
+    }
+
+    fn retry_update(&mut self) {
+        let now = Instant::now();
+        let due: Vec<Url> = self
+            .cache
+            .iter()
+            .filter_map(|(url, entry)| {
+                let LevelEntry::Failed(failure) = entry else {
+                    return None;
+                };
+                let due = failure.retry_at.is_some_and(|retry_at| retry_at <= now);
+                return due.then(|| url.clone());
+            })
+            .collect();
+
+        for url in due {
+            *self.cache.peek_mut(&url).unwrap() = LevelEntry::Queued;
+            self.queued.push_back(url);
+        }
+    }
+
+    fn failure(&mut self, url: &Url, error: LevelLoadError) -> LevelFailure {
+        let attempts = self.retries.entry(url.clone()).or_insert(0);
+        let retry_at = (error.is_transient() && *attempts < MAX_RETRIES).then(|| {
+            let delay = RETRY_BASE_DELAY * 2u32.pow(*attempts);
+            *attempts += 1;
+            return Instant::now() + delay;
+        });
+        return LevelFailure {
+            error: Arc::new(error),
+            retry_at,
+        };
+    }
+
+    fn running_update(&mut self) {
+        let mut finished = Vec::new();
+        self.running.retain(|url| {
+            let Some(load) = self.cache.peek(url).and_then(LevelEntry::load) else {
+                return false;
+            };
+            if !load.handle.is_finished() {
+                return true;
+            }
+            finished.push(url.clone());
+            return false;
+        });
+
+        for url in finished {
+            let (previous, load) = match self.cache.pop(&url) {
+                Some(LevelEntry::Loading(load)) => (None, load),
+                Some(LevelEntry::Reloading(level, load)) => (Some(level), load),
+                _ => continue,
+            };
+            let LevelLoad {
+                handle,
+                snapshot,
+                dirty,
+                ..
+            } = load;
+            let result = handle.join().unwrap();
+            let reload = dirty && !matches!(result, Err(LevelLoadError::Cancelled));
+            match (result, previous) {
+                (Ok(level), previous) => {
+                    if let Some(previous) = previous {
+                        self.size_bytes -= previous.size_bytes();
+                    }
+                    self.size_bytes += level.size_bytes();
+                    self.retries.remove(&url);
+                    self.reload_errors.remove(&url);
+                    if let (Some(watch), Some(snapshot)) = (&mut self.watch, &snapshot) {
+                        watch.track(&url, level.source_urls(), snapshot);
+                    }
+                    self.cache.put(url.clone(), LevelEntry::Ready(level));
+                    self.evict(&url);
+                }
+                (Err(LevelLoadError::Cancelled), None) => {}
+                (Err(LevelLoadError::Cancelled), Some(previous)) => {
+                    self.cache.put(url.clone(), LevelEntry::Ready(previous));
+                }
+                (Err(error), None) => {
+                    log::error!("failed to load level {url}: {}", error.chain().join(": "));
+                    let failure = self.failure(&url, error);
+                    self.cache.put(url.clone(), LevelEntry::Failed(failure));
+                    self.failed_evict();
+                }
+                (Err(error), Some(previous)) => {
+                    log::error!("failed to reload level {url}: {}", error.chain().join(": "));
+                    self.reload_errors.insert(url.clone(), Arc::new(error));
+                    self.cache.put(url.clone(), LevelEntry::Ready(previous));
+                }
+            }
+            if reload {
+                self.reload(&url);
+            }
+        }
+    }
+
+    fn watch_update(&mut self) {
+        let Some(watch) = &mut self.watch else {
+            return;
+        };
+        for url in watch.changed() {
+            self.reload(&url);
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.watch_update();
+        self.running_update();
+        self.retry_update();
+        self.queued_update();
+        self.prefetch_update();
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use url::Url;
+
+use crate::level::fetch::{FetchDiskCache, FetchLimits};
+use crate::level::level::LevelLoadError;
+use crate::level::{Level, LevelLoadCancel, LevelLoadParams, LevelLoadProgress, LevelPolicy};
+
+// What the cache needs from a loaded level to budget, prefetch and watch it.
+pub trait LevelCacheLevel: Send + Sync + 'static {
+    fn size_bytes(&self) -> u64;
+    // Where the level's portals lead, fragments included.
+    fn link_urls(&self) -> Vec<Url>;
+    // Everything the level was built from, watched for hot reload.
+    fn source_urls(&self) -> &[Url];
+}
+
+// Builds levels for the cache, one call per loader thread.
+pub trait LevelCacheLoader: Send + Sync + 'static {
+    type Level: LevelCacheLevel;
+
+    fn load(
+        &self,
+        url: Url,
+        cancel: &LevelLoadCancel,
+        progress: &LevelLoadProgress,
+    ) -> Result<Self::Level, LevelLoadError>;
+}
+
+// Loads levels onto the GPU with the configured fetch limits, disk cache, policy and
+// strictness.
+pub struct LevelLoader {
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    limits: FetchLimits,
+    disk_cache: Option<FetchDiskCache>,
+    policy: LevelPolicy,
+    strict: bool,
+}
+
+impl LevelLoader {
+    pub fn new(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        limits: FetchLimits,
+        disk_cache: Option<FetchDiskCache>,
+        policy: LevelPolicy,
+        strict: bool,
+    ) -> Self {
+        return Self {
+            device,
+            queue,
+            limits,
+            disk_cache,
+            policy,
+            strict,
+        };
+    }
+}
+
+impl LevelCacheLoader for LevelLoader {
+    type Level = Level;
+
+    fn load(
+        &self,
+        url: Url,
+        cancel: &LevelLoadCancel,
+        progress: &LevelLoadProgress,
+    ) -> Result<Level, LevelLoadError> {
+        return Level::load(
+            url,
+            LevelLoadParams {
+                limits: &self.limits,
+                disk_cache: self.disk_cache.as_ref(),
+                policy: &self.policy,
+                strict: self.strict,
+                cancel,
+                progress,
+                device: &self.device,
+                queue: &self.queue,
+            },
+        );
+    }
+}
+
+impl LevelCacheLevel for Level {
+    fn size_bytes(&self) -> u64 {
+        return Level::size_bytes(self);
+    }
+
+    fn link_urls(&self) -> Vec<Url> {
+        return self
+            .portals()
+            .map(|(_, portal)| portal.link_url().clone())
+            .collect();
+    }
+
+    fn source_urls(&self) -> &[Url] {
+        return Level::source_urls(self);
+    }
+}
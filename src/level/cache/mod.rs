@@ -0,0 +1,11 @@
+mod cache;
+mod loader;
+
+#[cfg(test)]
+mod test;
+
+pub use cache::{LevelCache, LevelCacheResult};
+pub use loader::{LevelCacheLevel, LevelCacheLoader, LevelLoader};
+
+#[cfg(test)]
+pub use cache::{MAX_FAILED, MAX_LOADERS, RETRY_BASE_DELAY};
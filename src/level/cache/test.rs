@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use url::Url;
+
+use super::*;
+use crate::level::fetch::FetchError;
+use crate::level::level::LevelLoadError;
+use crate::level::manifest::LevelManifestLoadError;
+use crate::level::{LevelLoadCancel, LevelLoadProgress};
+
+const LEVEL_BYTES: u64 = 10;
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct StubLevel {
+    links: Vec<Url>,
+    source_urls: Vec<Url>,
+    // Which load built this level, counting from 1.
+    generation: usize,
+}
+
+impl LevelCacheLevel for StubLevel {
+    fn size_bytes(&self) -> u64 {
+        return LEVEL_BYTES;
+    }
+
+    fn link_urls(&self) -> Vec<Url> {
+        return self.links.clone();
+    }
+
+    fn source_urls(&self) -> &[Url] {
+        return &self.source_urls;
+    }
+}
+
+#[derive(Default)]
+struct StubState {
+    links: HashMap<Url, Vec<Url>>,
+    source_urls: HashMap<Url, Vec<Url>>,
+    failures: Mutex<HashMap<Url, FetchError>>,
+    // Loads wait while this is set, until they are cancelled.
+    held: AtomicBool,
+    started: Mutex<Vec<Url>>,
+}
+
+impl StubState {
+    fn started(&self, url: &Url) -> usize {
+        return self
+            .started
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|started| *started == url)
+            .count();
+    }
+
+    fn started_total(&self) -> usize {
+        return self.started.lock().unwrap().len();
+    }
+}
+
+struct StubLoader(Arc<StubState>);
+
+impl LevelCacheLoader for StubLoader {
+    type Level = StubLevel;
+
+    fn load(
+        &self,
+        url: Url,
+        cancel: &LevelLoadCancel,
+        _progress: &LevelLoadProgress,
+    ) -> Result<StubLevel, LevelLoadError> {
+        let state = &self.0;
+        state.started.lock().unwrap().push(url.clone());
+        let generation = state.started(&url);
+        while state.held.load(Ordering::SeqCst) && !cancel.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        if cancel.is_cancelled() {
+            return Err(LevelLoadError::Cancelled);
+        }
+        if let Some(err) = state.failures.lock().unwrap().get(&url) {
+            return Err(LevelLoadError::Manifest(LevelManifestLoadError::Fetch(
+                *err,
+            )));
+        }
+        return Ok(StubLevel {
+            links: state.links.get(&url).cloned().unwrap_or_default(),
+            source_urls: state.source_urls.get(&url).cloned().unwrap_or_default(),
+            generation,
+        });
+    }
+}
+
+fn url(name: &str) -> Url {
+    return Url::parse(&format!("https://example.com/{name}/manifest.json")).unwrap();
+}
+
+// Halls named by `names`, each linking to the next.
+fn chain(names: &[&str]) -> HashMap<Url, Vec<Url>> {
+    return names
+        .windows(2)
+        .map(|pair| (url(pair[0]), vec![url(pair[1])]))
+        .collect();
+}
+
+fn cache_new(state: StubState, budget_bytes: u64) -> (LevelCache<StubLoader>, Arc<StubState>) {
+    let state = Arc::new(state);
+    let cache = LevelCache::new(StubLoader(Arc::clone(&state)), budget_bytes, false);
+    return (cache, state);
+}
+
+// Ticks the cache until `done` holds.
+fn settle(
+    cache: &mut LevelCache<StubLoader>,
+    mut done: impl FnMut(&mut LevelCache<StubLoader>) -> bool,
+) {
+    let deadline = Instant::now() + SETTLE_TIMEOUT;
+    while !done(cache) {
+        assert!(Instant::now() < deadline, "cache did not settle");
+        cache.update();
+        thread::sleep(Duration::from_millis(2));
+    }
+}
+
+fn ready(cache: &mut LevelCache<StubLoader>, url: &Url) -> Option<Arc<StubLevel>> {
+    return match cache.get(url) {
+        LevelCacheResult::Ready(level) => Some(level),
+        _ => None,
+    };
+}
+
+fn failed(cache: &mut LevelCache<StubLoader>, url: &Url) -> bool {
+    return matches!(cache.get(url), LevelCacheResult::Failed(_));
+}
+
+#[test]
+fn test_cache_caps_concurrent_loaders() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    state.held.store(true, Ordering::SeqCst);
+    let urls: Vec<Url> = (0..MAX_LOADERS + 2)
+        .map(|ix| url(&format!("hall-{ix}")))
+        .collect();
+    for url in &urls {
+        cache.get(url);
+    }
+
+    settle(&mut cache, |_| state.started_total() == MAX_LOADERS);
+    thread::sleep(Duration::from_millis(20));
+    cache.update();
+    assert_eq!(state.started_total(), MAX_LOADERS);
+
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| {
+        return urls.iter().all(|url| ready(cache, url).is_some());
+    });
+    assert_eq!(state.started_total(), urls.len());
+}
+
+#[test]
+fn test_cache_cancel_drops_running_load() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    state.held.store(true, Ordering::SeqCst);
+    let a = url("a");
+    cache.get(&a);
+    settle(&mut cache, |_| state.started(&a) == 1);
+
+    cache.cancel(&a);
+    settle(&mut cache, |cache| cache.progress(&a).is_none());
+
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| ready(cache, &a).is_some());
+    assert_eq!(state.started(&a), 2);
+}
+
+#[test]
+fn test_cache_prefetches_links_up_to_depth() {
+    let state = StubState {
+        links: chain(&["a", "b", "c", "d"]),
+        ..StubState::default()
+    };
+    let (mut cache, state) = cache_new(state, u64::MAX);
+    let (a, b, c, d) = (url("a"), url("b"), url("c"), url("d"));
+    cache.get(&a);
+    settle(&mut cache, |cache| ready(cache, &a).is_some());
+
+    settle(&mut cache, |cache| {
+        cache.neighbourhood_update(&a, 2);
+        return state.started(&c) == 1 && cache.progress(&c).is_none();
+    });
+    for _ in 0..10 {
+        cache.neighbourhood_update(&a, 2);
+        cache.update();
+        thread::sleep(Duration::from_millis(2));
+    }
+
+    assert_eq!(state.started(&b), 1);
+    assert_eq!(state.started(&d), 0);
+}
+
+#[test]
+fn test_cache_evicts_least_recent_outside_neighbourhood() {
+    let state = StubState {
+        links: chain(&["a", "b"]),
+        ..StubState::default()
+    };
+    let (mut cache, state) = cache_new(state, LEVEL_BYTES * 2 + LEVEL_BYTES / 2);
+    let (a, b, x, y) = (url("a"), url("b"), url("x"), url("y"));
+    cache.get(&x);
+    cache.get(&y);
+    settle(&mut cache, |cache| {
+        return ready(cache, &x).is_some() && ready(cache, &y).is_some();
+    });
+
+    cache.get(&a);
+    settle(&mut cache, |cache| {
+        cache.neighbourhood_update(&a, 1);
+        return ready(cache, &a).is_some() && state.started(&b) == 1;
+    });
+    settle(&mut cache, |cache| {
+        cache.neighbourhood_update(&a, 1);
+        return cache.progress(&b).is_none();
+    });
+
+    assert!(ready(&mut cache, &a).is_some());
+    assert!(ready(&mut cache, &b).is_some());
+    // Evicted levels load afresh.
+    assert!(matches!(cache.get(&x), LevelCacheResult::Loading(_)));
+    assert!(matches!(cache.get(&y), LevelCacheResult::Loading(_)));
+}
+
+#[test]
+fn test_cache_keeps_pinned_levels_over_budget() {
+    let state = StubState {
+        links: chain(&["a", "b"]),
+        ..StubState::default()
+    };
+    let (mut cache, state) = cache_new(state, LEVEL_BYTES);
+    let (a, b) = (url("a"), url("b"));
+    cache.get(&a);
+    settle(&mut cache, |cache| {
+        cache.neighbourhood_update(&a, 1);
+        return state.started(&b) == 1 && cache.progress(&b).is_none();
+    });
+
+    assert_eq!(ready(&mut cache, &a).unwrap().generation, 1);
+    assert_eq!(ready(&mut cache, &b).unwrap().generation, 1);
+}
+
+#[test]
+fn test_cache_retries_only_transient_failures_after_backoff() {
+    let (transient, permanent) = (url("transient"), url("permanent"));
+    let state = StubState {
+        failures: Mutex::new(HashMap::from([
+            (transient.clone(), FetchError::Timeout),
+            (permanent.clone(), FetchError::Status(404)),
+        ])),
+        ..StubState::default()
+    };
+    let (mut cache, state) = cache_new(state, u64::MAX);
+    cache.get(&transient);
+    cache.get(&permanent);
+    settle(&mut cache, |cache| {
+        return failed(cache, &transient) && failed(cache, &permanent);
+    });
+
+    cache.update();
+    assert_eq!(state.started(&transient), 1);
+
+    state.failures.lock().unwrap().clear();
+    thread::sleep(RETRY_BASE_DELAY);
+    settle(&mut cache, |cache| ready(cache, &transient).is_some());
+    assert_eq!(state.started(&transient), 2);
+    assert!(failed(&mut cache, &permanent));
+    assert_eq!(state.started(&permanent), 1);
+}
+
+#[test]
+fn test_cache_reload_serves_previous_level_until_replaced() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    let a = url("a");
+    cache.get(&a);
+    settle(&mut cache, |cache| ready(cache, &a).is_some());
+
+    state.held.store(true, Ordering::SeqCst);
+    cache.reload(&a);
+    settle(&mut cache, |_| state.started(&a) == 2);
+    assert_eq!(ready(&mut cache, &a).unwrap().generation, 1);
+
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| {
+        ready(cache, &a).unwrap().generation == 2
+    });
+}
+
+#[test]
+fn test_cache_reload_during_load_loads_again() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    let a = url("a");
+    state.held.store(true, Ordering::SeqCst);
+    cache.get(&a);
+    settle(&mut cache, |_| state.started(&a) == 1);
+
+    cache.reload(&a);
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| {
+        ready(cache, &a).is_some_and(|level| level.generation == 2)
+    });
+}
+
+#[test]
+fn test_cache_watch_sees_edits_made_during_load() {
+    let dir = std::env::temp_dir().join(format!("halls-cache-watch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("manifest.json");
+    std::fs::write(&path, "{}").unwrap();
+    let a = Url::from_file_path(&path).unwrap();
+
+    let state = Arc::new(StubState {
+        source_urls: HashMap::from([(a.clone(), vec![a.clone()])]),
+        ..StubState::default()
+    });
+    let mut cache = LevelCache::new(StubLoader(Arc::clone(&state)), u64::MAX, true);
+    state.held.store(true, Ordering::SeqCst);
+    cache.get(&a);
+    settle(&mut cache, |_| state.started(&a) == 1);
+
+    let file = File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000))
+        .unwrap();
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| {
+        ready(cache, &a).is_some_and(|level| level.generation == 2)
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cache_failed_reload_keeps_previous_level() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    let a = url("a");
+    cache.get(&a);
+    settle(&mut cache, |cache| ready(cache, &a).is_some());
+
+    state
+        .failures
+        .lock()
+        .unwrap()
+        .insert(a.clone(), FetchError::Status(404));
+    cache.reload(&a);
+    settle(&mut cache, |cache| cache.reload_error(&a).is_some());
+
+    assert_eq!(ready(&mut cache, &a).unwrap().generation, 1);
+}
+
+#[test]
+fn test_cache_reload_waits_for_a_free_loader() {
+    let (mut cache, state) = cache_new(StubState::default(), u64::MAX);
+    let a = url("a");
+    cache.get(&a);
+    settle(&mut cache, |cache| ready(cache, &a).is_some());
+
+    state.held.store(true, Ordering::SeqCst);
+    let others: Vec<Url> = (0..MAX_LOADERS)
+        .map(|ix| url(&format!("hall-{ix}")))
+        .collect();
+    for other in &others {
+        cache.get(other);
+    }
+    settle(&mut cache, |_| state.started_total() == 1 + MAX_LOADERS);
+    cache.reload(&a);
+    cache.update();
+    thread::sleep(Duration::from_millis(20));
+    cache.update();
+    assert_eq!(state.started(&a), 1);
+    assert_eq!(ready(&mut cache, &a).unwrap().generation, 1);
+
+    state.held.store(false, Ordering::SeqCst);
+    settle(&mut cache, |cache| {
+        ready(cache, &a).unwrap().generation == 2
+    });
+}
+
+#[test]
+fn test_cache_keeps_only_recent_failures() {
+    let urls: Vec<Url> = (0..MAX_FAILED + 2)
+        .map(|ix| url(&format!("missing-{ix}")))
+        .collect();
+    let state = StubState {
+        failures: Mutex::new(
+            urls.iter()
+                .map(|url| (url.clone(), FetchError::Status(404)))
+                .collect(),
+        ),
+        ..StubState::default()
+    };
+    let (mut cache, state) = cache_new(state, u64::MAX);
+    for url in &urls {
+        cache.get(url);
+        settle(&mut cache, |cache| failed(cache, url));
+    }
+
+    // The oldest failures were dropped, so asking again loads them afresh.
+    for url in &urls[..2] {
+        assert!(matches!(cache.get(url), LevelCacheResult::Loading(_)));
+    }
+    for url in &urls[2..] {
+        assert!(failed(&mut cache, url));
+        assert_eq!(state.started(url), 1);
+    }
+}
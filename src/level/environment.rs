@@ -0,0 +1,141 @@
+use url::Url;
+
+use crate::color::Color;
+use crate::graphics::pipeline::level::{PipelineLevelFog, PipelineLevelFogMode};
+use crate::graphics::pipeline::sky::PipelineSkyBindGroupTexture;
+use crate::graphics::texture::TextureCube;
+use crate::level::fetch::{FetchBatch, FetchError};
+
+use super::manifest::{
+    LevelManifestEnvironment, LevelManifestFog, LevelManifestFogMode, LevelManifestSky,
+};
+use super::sky::{sky_cubemap_size, sky_equirectangular_faces, LevelSkyError};
+
+#[derive(Debug)]
+pub enum LevelEnvironmentLoadError {
+    URLJoin(String),
+    Fetch(String, FetchError),
+    Decode(String, image::ImageError),
+    Sky(LevelSkyError),
+}
+
+impl std::fmt::Display for LevelEnvironmentLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelEnvironmentLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelEnvironmentLoadError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelEnvironmentLoadError::Decode(url, _) => {
+                write!(f, "failed to decode image {url}")
+            }
+            LevelEnvironmentLoadError::Sky(_) => write!(f, "invalid sky"),
+        };
+    }
+}
+
+impl std::error::Error for LevelEnvironmentLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelEnvironmentLoadError::URLJoin(_) => None,
+            LevelEnvironmentLoadError::Fetch(_, err) => Some(err),
+            LevelEnvironmentLoadError::Decode(_, err) => Some(err),
+            LevelEnvironmentLoadError::Sky(err) => Some(err),
+        };
+    }
+}
+
+// Colors are linear like surface colors, so a fog matches the surfaces it fades into.
+fn color_linear(color: Color) -> [f32; 4] {
+    return [color.r, color.g, color.b, color.a].map(|channel| channel as f32 / 255.0);
+}
+
+fn image_load(
+    base_url: &Url,
+    batch: &FetchBatch,
+    href: &str,
+) -> Result<image::RgbaImage, LevelEnvironmentLoadError> {
+    let url = base_url
+        .join(href)
+        .map_err(|_| LevelEnvironmentLoadError::URLJoin(href.to_string()))?;
+    let data = batch
+        .get(&url)
+        .map_err(|err| LevelEnvironmentLoadError::Fetch(url.to_string(), err))?;
+    let image = image::load_from_memory(data)
+        .map_err(|err| LevelEnvironmentLoadError::Decode(url.to_string(), err))?;
+    return Ok(image.to_rgba8());
+}
+
+pub struct LevelEnvironment {
+    pub clear: wgpu::Color,
+    pub fog: PipelineLevelFog,
+    pub sky: Option<PipelineSkyBindGroupTexture>,
+    pub sky_bytes: u64,
+}
+
+impl LevelEnvironment {
+    fn fog_build(fog: &LevelManifestFog) -> PipelineLevelFog {
+        let mode = match fog.mode() {
+            LevelManifestFogMode::Linear => PipelineLevelFogMode::Linear,
+            LevelManifestFogMode::Exponential => PipelineLevelFogMode::Exponential,
+        };
+        return PipelineLevelFog {
+            mode,
+            color: color_linear(fog.color()),
+            start: fog.start(),
+            density: fog.density(),
+        };
+    }
+
+    pub fn load(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        base_url: &Url,
+        batch: &FetchBatch,
+        environment: Option<&LevelManifestEnvironment>,
+    ) -> Result<Self, LevelEnvironmentLoadError> {
+        let Some(environment) = environment else {
+            return Ok(Self {
+                clear: wgpu::Color::BLACK,
+                fog: PipelineLevelFog::default(),
+                sky: None,
+                sky_bytes: 0,
+            });
+        };
+
+        let [r, g, b, a] = color_linear(environment.clear()).map(f64::from);
+        let fog = environment.fog().map(Self::fog_build).unwrap_or_default();
+
+        let faces = match environment.sky() {
+            Some(LevelManifestSky::Equirectangular { image }) => {
+                let image = image_load(base_url, batch, image)?;
+                Some(sky_equirectangular_faces(&image).map_err(LevelEnvironmentLoadError::Sky)?)
+            }
+            Some(LevelManifestSky::Cubemap { faces }) => {
+                let mut images = Vec::with_capacity(faces.len());
+                for face in faces {
+                    images.push(image_load(base_url, batch, face)?);
+                }
+                Some(images.try_into().unwrap())
+            }
+            None => None,
+        };
+        let (sky, sky_bytes) = match faces {
+            Some(faces) => {
+                let size = sky_cubemap_size(&faces).map_err(LevelEnvironmentLoadError::Sky)?;
+                let texture = TextureCube::new(device, queue, size, &faces);
+                let sky_bytes = texture.size_bytes();
+                (
+                    Some(PipelineSkyBindGroupTexture::new(device, &texture)),
+                    sky_bytes,
+                )
+            }
+            None => (None, 0),
+        };
+
+        return Ok(Self {
+            clear: wgpu::Color { r, g, b, a },
+            fog,
+            sky,
+            sky_bytes,
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use url::Url;
+
+use super::bundle::FetchBundle;
+use super::disk::FetchDiskCache;
+use super::fetch::{fetch_pinned, fetch_tracked, FetchError};
+use super::integrity::{FetchDigest, FetchIntegrity};
+use super::limits::FetchLimits;
+use crate::level::{LevelLoadCancel, LevelLoadProgress};
+
+pub const BATCH_WORKERS: usize = 8;
+
+// Bytes fetched so far for one level, held against `FetchLimits::max_level_bytes` by every
+// fetch made for it.
+#[derive(Default)]
+pub struct FetchLevelBudget {
+    spent_bytes: AtomicU64,
+}
+
+impl FetchLevelBudget {
+    fn charge(&self, data: Vec<u8>, limits: &FetchLimits) -> Result<Vec<u8>, FetchError> {
+        let len = data.len() as u64;
+        let total = self.spent_bytes.fetch_add(len, Ordering::Relaxed) + len;
+        if total > limits.max_level_bytes {
+            return Err(FetchError::TooLarge);
+        }
+        return Ok(data);
+    }
+}
+
+// One asset of a level, pinned when `integrity` has its digest and charged to `budget`.
+// Batches fetch each of their URLs this way; surface libraries, which are read before the
+// batch's URLs are known, call it directly.
+pub fn fetch_level_asset(
+    url: &Url,
+    integrity: &FetchIntegrity,
+    limits: &FetchLimits,
+    disk_cache: Option<&FetchDiskCache>,
+    budget: &FetchLevelBudget,
+    progress: &LevelLoadProgress,
+) -> Result<Vec<u8>, FetchError> {
+    let data = match integrity.get(url) {
+        Some(digest) => fetch_pinned(url, *digest, limits, disk_cache, progress)?,
+        None => fetch_tracked(url, limits, disk_cache, progress)?,
+    };
+    return budget.charge(data, limits);
+}
+
+pub struct FetchBatch {
+    results: HashMap<Url, Result<Vec<u8>, FetchError>>,
+}
+
+impl FetchBatch {
+    // Workers stop picking up URLs once `cancel` fires, leaving the batch incomplete;
+    // callers must check the token before reading results.
+    pub fn load(
+        urls: impl IntoIterator<Item = Url>,
+        integrity: &FetchIntegrity,
+        limits: &FetchLimits,
+        disk_cache: Option<&FetchDiskCache>,
+        budget: &FetchLevelBudget,
+        cancel: &LevelLoadCancel,
+        progress: &LevelLoadProgress,
+    ) -> Self {
+        let mut unique: Vec<Url> = Vec::new();
+        for url in urls {
+            if !unique.contains(&url) {
+                unique.push(url);
+            }
+        }
+
+        progress.assets_add(unique.len());
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|scope| {
+            let next = &next;
+            let unique = &unique;
+            for _ in 0..BATCH_WORKERS.min(unique.len()) {
+                let tx = tx.clone();
+                scope.spawn(move || loop {
+                    if cancel.is_cancelled() {
+                        return;
+                    }
+                    let ix = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(url) = unique.get(ix) else {
+                        return;
+                    };
+                    let result =
+                        fetch_level_asset(url, integrity, limits, disk_cache, budget, progress);
+                    progress.asset_done();
+                    tx.send((ix, result)).unwrap();
+                });
+            }
+        });
+        drop(tx);
+
+        let mut results = HashMap::with_capacity(unique.len());
+        for (ix, result) in rx {
+            results.insert(unique[ix].clone(), result);
+        }
+        return Self { results };
+    }
+
+    pub fn from_bundle(
+        urls: impl IntoIterator<Item = Url>,
+        integrity: &FetchIntegrity,
+        mut bundle: FetchBundle,
+    ) -> Self {
+        let mut results = HashMap::new();
+        for url in urls {
+            results.entry(url).or_insert_with_key(|url| {
+                let data = bundle.take(url)?;
+                let pinned = integrity.get(url);
+                if pinned.is_some_and(|digest| *digest != FetchDigest::of(&data)) {
+                    return Err(FetchError::Integrity);
+                }
+                return Ok(data);
+            });
+        }
+        return Self { results };
+    }
+
+    // URLs the batch was not asked for, or did not reach before being cancelled, are
+    // `NotInBatch`.
+    pub fn get(&self, url: &Url) -> Result<&[u8], FetchError> {
+        return match self.results.get(url) {
+            Some(Ok(data)) => Ok(data),
+            Some(Err(err)) => Err(*err),
+            None => Err(FetchError::NotInBatch),
+        };
+    }
+}
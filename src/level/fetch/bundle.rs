@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use url::Url;
+
+use super::disk::FetchDiskCache;
+use super::fetch::{fetch_tracked, FetchError};
+use super::limits::FetchLimits;
+use crate::level::LevelLoadProgress;
+
+const BUNDLE_EXTENSIONS: [&str; 2] = [".zip", ".halls"];
+const MANIFEST_NAME: &str = "manifest.json";
+
+// A level packaged as a single archive. Entries are keyed by the URL they resolve to
+// beneath `base_url`, so hrefs relative to the bundle join onto it as they would onto
+// a hosted directory.
+pub struct FetchBundle {
+    base_url: Url,
+    entries: HashMap<Url, Vec<u8>>,
+}
+
+impl FetchBundle {
+    pub fn is_bundle(url: &Url) -> bool {
+        let path = url.path().to_ascii_lowercase();
+        return BUNDLE_EXTENSIONS.iter().any(|ext| path.ends_with(ext));
+    }
+
+    pub fn load(
+        url: &Url,
+        limits: &FetchLimits,
+        disk_cache: Option<&FetchDiskCache>,
+        progress: &LevelLoadProgress,
+    ) -> Result<Self, FetchError> {
+        let archive_limits = FetchLimits {
+            max_asset_bytes: limits.max_level_bytes,
+            ..*limits
+        };
+        progress.assets_add(1);
+        let data = fetch_tracked(url, &archive_limits, disk_cache, progress)?;
+        progress.asset_done();
+        return Self::from_bytes(url, &data, limits);
+    }
+
+    pub fn from_bytes(url: &Url, data: &[u8], limits: &FetchLimits) -> Result<Self, FetchError> {
+        let mut base_url = url.clone();
+        base_url.set_path(&format!("{}/", url.path()));
+        base_url.set_query(None);
+        base_url.set_fragment(None);
+
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(data)).map_err(|_| FetchError::Archive)?;
+        let mut entries = HashMap::with_capacity(archive.len());
+        let mut total_bytes = 0;
+        for ix in 0..archive.len() {
+            let file = archive.by_index(ix).map_err(|_| FetchError::Archive)?;
+            if file.is_dir() {
+                continue;
+            }
+            let Ok(entry_url) = base_url.join(file.name()) else {
+                continue;
+            };
+
+            // Declared sizes can lie, so the read itself is capped.
+            let remaining = limits.max_level_bytes - total_bytes;
+            let mut entry = Vec::new();
+            file.take(remaining + 1)
+                .read_to_end(&mut entry)
+                .map_err(|_| FetchError::Archive)?;
+            total_bytes += entry.len() as u64;
+            if total_bytes > limits.max_level_bytes {
+                return Err(FetchError::TooLarge);
+            }
+            entries.insert(entry_url, entry);
+        }
+
+        return Ok(Self { base_url, entries });
+    }
+
+    pub fn base_url(&self) -> &Url {
+        return &self.base_url;
+    }
+
+    pub fn manifest_url(&self) -> Url {
+        return self.base_url.join(MANIFEST_NAME).unwrap();
+    }
+
+    pub fn get(&self, url: &Url) -> Result<&[u8], FetchError> {
+        return self
+            .entries
+            .get(url)
+            .map(Vec::as_slice)
+            .ok_or(FetchError::NotInBundle);
+    }
+
+    pub fn take(&mut self, url: &Url) -> Result<Vec<u8>, FetchError> {
+        return self.entries.remove(url).ok_or(FetchError::NotInBundle);
+    }
+}
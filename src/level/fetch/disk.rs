@@ -0,0 +1,142 @@
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+const CACHE_PATH: &str = "halls/fetch";
+const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+const DATA_EXTENSION: &str = "bin";
+const META_EXTENSION: &str = "json";
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn cache_dir() -> Option<PathBuf> {
+    let dir = match std::env::var("XDG_CACHE_HOME") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from(std::env::var("HOME").ok()?).join(".cache"),
+    };
+    return Some(dir.join(CACHE_PATH));
+}
+
+fn url_key(url: &Url) -> String {
+    // FNV-1a keeps file names stable across builds, unlike the std hasher.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.as_str().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return format!("{hash:016x}");
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FetchDiskCacheMeta {
+    pub url: Url,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+pub struct FetchDiskCacheEntry {
+    pub meta: FetchDiskCacheMeta,
+    pub data: Vec<u8>,
+}
+
+pub struct FetchDiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl FetchDiskCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        return Self { dir, max_bytes };
+    }
+
+    // The per-user cache, or none when the environment names no cache or home directory.
+    pub fn user() -> Option<Self> {
+        return cache_dir().map(|dir| Self::new(dir, DEFAULT_MAX_BYTES));
+    }
+
+    fn path(&self, url: &Url, extension: &str) -> PathBuf {
+        return self.dir.join(url_key(url)).with_extension(extension);
+    }
+
+    pub fn get(&self, url: &Url) -> Option<FetchDiskCacheEntry> {
+        let meta_data = fs::read(self.path(url, META_EXTENSION)).ok()?;
+        let meta: FetchDiskCacheMeta = serde_json::from_slice(&meta_data).ok()?;
+        if meta.url != *url {
+            return None;
+        }
+        let data = fs::read(self.path(url, DATA_EXTENSION)).ok()?;
+        return Some(FetchDiskCacheEntry { meta, data });
+    }
+
+    pub fn touch(&self, url: &Url) {
+        if let Ok(file) = File::options()
+            .write(true)
+            .open(self.path(url, DATA_EXTENSION))
+        {
+            let _ = file.set_modified(SystemTime::now());
+        }
+    }
+
+    pub fn put(&self, meta: &FetchDiskCacheMeta, data: &[u8]) {
+        if data.len() as u64 > self.max_bytes || fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        let Ok(meta_data) = serde_json::to_vec(meta) else {
+            return;
+        };
+        let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp_path = self
+            .dir
+            .join(format!("{}-{}.tmp", std::process::id(), counter));
+        let data_path = self.path(&meta.url, DATA_EXTENSION);
+        let meta_path = self.path(&meta.url, META_EXTENSION);
+
+        if fs::write(&temp_path, data).is_err() || fs::rename(&temp_path, &data_path).is_err() {
+            let _ = fs::remove_file(&temp_path);
+            return;
+        }
+        if fs::write(&temp_path, meta_data).is_err() || fs::rename(&temp_path, &meta_path).is_err()
+        {
+            let _ = fs::remove_file(&temp_path);
+            let _ = fs::remove_file(&data_path);
+            return;
+        }
+
+        self.evict();
+    }
+
+    fn evict(&self) {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+        for entry in dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(DATA_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((modified, metadata.len(), path));
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            let _ = fs::remove_file(path.with_extension(META_EXTENSION));
+            let _ = fs::remove_file(&path);
+            total -= len;
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::fs;
+use std::io::{self, Read};
+use url::Url;
+
+use super::disk::{FetchDiskCache, FetchDiskCacheMeta};
+use super::integrity::FetchDigest;
+use super::limits::FetchLimits;
+use crate::level::LevelLoadProgress;
+
+const HTTP_NOT_MODIFIED: u16 = 304;
+// Documents a server answers with in place of an asset, such as error, login or captive
+// portal pages. No level file is ever one of these.
+const REJECTED_CONTENT_TYPES: [&str; 7] = [
+    "text/html",
+    "application/xhtml+xml",
+    "text/xml",
+    "application/xml",
+    "text/css",
+    "text/javascript",
+    "application/javascript",
+];
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub enum FetchError {
+    HTTP,
+    Status(u16),
+    IO,
+    InvalidScheme,
+    Timeout,
+    TooLarge,
+    ContentType,
+    Archive,
+    NotInBundle,
+    NotInBatch,
+    Integrity,
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            FetchError::HTTP => write!(f, "request failed"),
+            FetchError::Status(code) => write!(f, "server responded with HTTP {code}"),
+            FetchError::IO => write!(f, "read failed"),
+            FetchError::InvalidScheme => write!(f, "unsupported URL scheme"),
+            FetchError::Timeout => write!(f, "request timed out"),
+            FetchError::TooLarge => write!(f, "response too large"),
+            FetchError::ContentType => write!(f, "unexpected content type"),
+            FetchError::Archive => write!(f, "invalid level bundle"),
+            FetchError::NotInBundle => write!(f, "not found in level bundle"),
+            FetchError::NotInBatch => write!(f, "not fetched with the level"),
+            FetchError::Integrity => write!(f, "content does not match its sha256 digest"),
+        };
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl FetchError {
+    // Failures worth retrying: the same request may succeed once the network or server
+    // recovers.
+    pub fn is_transient(&self) -> bool {
+        return match self {
+            FetchError::HTTP | FetchError::Timeout => true,
+            FetchError::Status(code) => *code == 429 || *code >= 500,
+            FetchError::IO
+            | FetchError::InvalidScheme
+            | FetchError::TooLarge
+            | FetchError::ContentType
+            | FetchError::Archive
+            | FetchError::NotInBundle
+            | FetchError::NotInBatch
+            | FetchError::Integrity => false,
+        };
+    }
+}
+
+fn io_error_is_timeout(err: &io::Error) -> bool {
+    return matches!(
+        err.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    );
+}
+
+fn transport_error(err: &ureq::Transport) -> FetchError {
+    let source = std::error::Error::source(err).and_then(|s| s.downcast_ref::<io::Error>());
+    if source.is_some_and(io_error_is_timeout) {
+        return FetchError::Timeout;
+    }
+    return FetchError::HTTP;
+}
+
+fn read_limited(
+    reader: impl Read,
+    max_bytes: u64,
+    progress: &LevelLoadProgress,
+) -> Result<Vec<u8>, FetchError> {
+    let mut reader = reader.take(max_bytes.saturating_add(1));
+    let mut data = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK_BYTES];
+    loop {
+        let len = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) if io_error_is_timeout(&err) => return Err(FetchError::Timeout),
+            Err(_) => return Err(FetchError::IO),
+        };
+        data.extend_from_slice(&chunk[..len]);
+        progress.bytes_add(len as u64);
+    }
+    if data.len() as u64 > max_bytes {
+        return Err(FetchError::TooLarge);
+    }
+    return Ok(data);
+}
+
+// HTTP responses are revalidated against and stored in `disk_cache` when one is given.
+pub fn fetch(
+    url: &Url,
+    limits: &FetchLimits,
+    disk_cache: Option<&FetchDiskCache>,
+) -> Result<Vec<u8>, FetchError> {
+    return fetch_tracked(url, limits, disk_cache, &LevelLoadProgress::default());
+}
+
+// As `fetch`, reporting the asset and the bytes received to `progress` as they arrive.
+pub fn fetch_tracked(
+    url: &Url,
+    limits: &FetchLimits,
+    disk_cache: Option<&FetchDiskCache>,
+    progress: &LevelLoadProgress,
+) -> Result<Vec<u8>, FetchError> {
+    match url.scheme() {
+        "http" | "https" => {
+            return fetch_http(url, limits, disk_cache, progress);
+        }
+        "file" => {
+            let path = url.to_file_path().map_err(|_| FetchError::IO)?;
+            let file = fs::File::open(&path).map_err(|_| FetchError::IO)?;
+            let len = file.metadata().map(|meta| meta.len()).ok();
+            progress.asset_start(url, len);
+            return read_limited(file, limits.max_asset_bytes, progress);
+        }
+        _ => {
+            return Err(FetchError::InvalidScheme);
+        }
+    };
+}
+
+// As `fetch_tracked`, failing unless the content matches `digest`. Verified content is
+// also cached by digest, so the same bytes behind another URL are not downloaded again.
+pub fn fetch_pinned(
+    url: &Url,
+    digest: FetchDigest,
+    limits: &FetchLimits,
+    disk_cache: Option<&FetchDiskCache>,
+    progress: &LevelLoadProgress,
+) -> Result<Vec<u8>, FetchError> {
+    let content_url = digest.cache_url();
+    if let Some(disk_cache) = disk_cache {
+        if let Some(cached) = disk_cache.get(&content_url) {
+            if FetchDigest::of(&cached.data) == digest {
+                disk_cache.touch(&content_url);
+                progress.asset_start(url, Some(cached.data.len() as u64));
+                progress.bytes_add(cached.data.len() as u64);
+                return Ok(cached.data);
+            }
+        }
+    }
+
+    let data = fetch_tracked(url, limits, disk_cache, progress)?;
+    if FetchDigest::of(&data) != digest {
+        return Err(FetchError::Integrity);
+    }
+    if let Some(disk_cache) = disk_cache {
+        let meta = FetchDiskCacheMeta {
+            url: content_url,
+            etag: None,
+            last_modified: None,
+        };
+        disk_cache.put(&meta, &data);
+    }
+    return Ok(data);
+}
+
+fn fetch_http(
+    url: &Url,
+    limits: &FetchLimits,
+    disk_cache: Option<&FetchDiskCache>,
+    progress: &LevelLoadProgress,
+) -> Result<Vec<u8>, FetchError> {
+    let cached = disk_cache
+        .and_then(|disk_cache| disk_cache.get(url))
+        .filter(|cached| cached.data.len() as u64 <= limits.max_asset_bytes);
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(limits.connect_timeout())
+        .timeout_read(limits.read_timeout())
+        .timeout(limits.request_timeout())
+        .build();
+    let mut request = agent.get(url.as_str());
+    if let Some(ref cached) = cached {
+        if let Some(ref etag) = cached.meta.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        if let Some(ref last_modified) = cached.meta.last_modified {
+            request = request.set("If-Modified-Since", last_modified);
+        }
+    }
+
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Transport(err)) => {
+            let Some(cached) = cached else {
+                return Err(transport_error(&err));
+            };
+            if let Some(disk_cache) = disk_cache {
+                disk_cache.touch(url);
+            }
+            progress.asset_start(url, Some(cached.data.len() as u64));
+            progress.bytes_add(cached.data.len() as u64);
+            return Ok(cached.data);
+        }
+        Err(ureq::Error::Status(code, _)) => {
+            return Err(FetchError::Status(code));
+        }
+    };
+
+    if response.status() == HTTP_NOT_MODIFIED {
+        let Some(cached) = cached else {
+            return Err(FetchError::HTTP);
+        };
+        if let Some(disk_cache) = disk_cache {
+            disk_cache.touch(url);
+        }
+        progress.asset_start(url, Some(cached.data.len() as u64));
+        progress.bytes_add(cached.data.len() as u64);
+        return Ok(cached.data);
+    }
+
+    let content_type = response.content_type();
+    if REJECTED_CONTENT_TYPES
+        .iter()
+        .any(|rejected| content_type.eq_ignore_ascii_case(rejected))
+    {
+        return Err(FetchError::ContentType);
+    }
+    let content_length = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<u64>().ok());
+    if content_length.is_some_and(|len| len > limits.max_asset_bytes) {
+        return Err(FetchError::TooLarge);
+    }
+
+    let meta = FetchDiskCacheMeta {
+        url: url.clone(),
+        etag: response.header("ETag").map(str::to_string),
+        last_modified: response.header("Last-Modified").map(str::to_string),
+    };
+    progress.asset_start(url, content_length);
+    let data = read_limited(response.into_reader(), limits.max_asset_bytes, progress)?;
+    if let Some(disk_cache) = disk_cache {
+        disk_cache.put(&meta, &data);
+    }
+    return Ok(data);
+}
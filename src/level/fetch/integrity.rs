@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+const DIGEST_LEN: usize = 32;
+
+// Expected SHA-256 digests of pinned assets, keyed by resolved URL.
+pub type FetchIntegrity = HashMap<Url, FetchDigest>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FetchDigest([u8; DIGEST_LEN]);
+
+impl FetchDigest {
+    pub fn of(data: &[u8]) -> Self {
+        return Self(Sha256::digest(data).into());
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != DIGEST_LEN * 2 || !hex.is_ascii() {
+            return None;
+        }
+        let mut digest = [0; DIGEST_LEN];
+        for (ix, byte) in digest.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[ix * 2..ix * 2 + 2], 16).ok()?;
+        }
+        return Some(Self(digest));
+    }
+
+    pub fn to_hex(self) -> String {
+        return self.0.iter().map(|byte| format!("{byte:02x}")).collect();
+    }
+
+    // Key for the content-addressed entry in the disk cache, shared by every URL that
+    // serves these bytes.
+    pub fn cache_url(self) -> Url {
+        return Url::parse(&format!("urn:sha256:{}", self.to_hex())).unwrap();
+    }
+}
+
+impl From<FetchDigest> for String {
+    fn from(digest: FetchDigest) -> Self {
+        return digest.to_hex();
+    }
+}
+
+impl TryFrom<String> for FetchDigest {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        return Self::from_hex(&hex).ok_or_else(|| format!("invalid sha256 digest \"{hex}\""));
+    }
+}
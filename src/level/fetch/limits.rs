@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_MAX_ASSET_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_MAX_LEVEL_BYTES: u64 = 256 * 1024 * 1024;
+
+fn request_timeout_secs_default() -> u64 {
+    return DEFAULT_REQUEST_TIMEOUT_SECS;
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FetchLimits {
+    pub connect_timeout_secs: u64,
+    // Between reads, so a server trickling bytes keeps the request alive.
+    pub read_timeout_secs: u64,
+    // For the whole request, which bounds a trickling server too.
+    #[serde(default = "request_timeout_secs_default")]
+    pub request_timeout_secs: u64,
+    pub max_asset_bytes: u64,
+    pub max_level_bytes: u64,
+}
+
+impl FetchLimits {
+    pub fn connect_timeout(&self) -> Duration {
+        return Duration::from_secs(self.connect_timeout_secs);
+    }
+
+    pub fn read_timeout(&self) -> Duration {
+        return Duration::from_secs(self.read_timeout_secs);
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        return Duration::from_secs(self.request_timeout_secs);
+    }
+}
+
+impl Default for FetchLimits {
+    fn default() -> Self {
+        return Self {
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            read_timeout_secs: DEFAULT_READ_TIMEOUT_SECS,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            max_asset_bytes: DEFAULT_MAX_ASSET_BYTES,
+            max_level_bytes: DEFAULT_MAX_LEVEL_BYTES,
+        };
+    }
+}
@@ -0,0 +1,23 @@
+mod batch;
+mod bundle;
+mod disk;
+mod fetch;
+mod integrity;
+mod limits;
+
+#[cfg(test)]
+mod test;
+
+pub use batch::{fetch_level_asset, FetchBatch, FetchLevelBudget};
+pub use bundle::FetchBundle;
+pub use disk::FetchDiskCache;
+pub use fetch::{fetch, FetchError};
+pub use integrity::{FetchDigest, FetchIntegrity};
+pub use limits::FetchLimits;
+
+#[cfg(test)]
+pub use batch::BATCH_WORKERS;
+#[cfg(test)]
+pub use disk::FetchDiskCacheMeta;
+#[cfg(test)]
+pub use fetch::{fetch_pinned, fetch_tracked};
@@ -0,0 +1,681 @@
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use url::Url;
+
+use super::*;
+use crate::level::{LevelLoadCancel, LevelLoadProgress};
+use crate::stand_in::{stand_in_serve_with, StandIn, StandInOptions};
+
+const ETAG: &str = "\"v1\"";
+const BODY: &[u8] = b"level asset";
+
+const ASSET: &str = "asset.bin";
+
+// Serves `BODY` at `ASSET` for `connections` requests, answering 304 when revalidated.
+fn asset_serve(connections: usize) -> (StandIn, Url) {
+    let stand_in = stand_in_serve_with(
+        [(ASSET, BODY.to_vec())],
+        StandInOptions {
+            etag: Some(ETAG),
+            connections: Some(connections),
+            ..StandInOptions::default()
+        },
+    );
+    let url = stand_in.url.join(ASSET).unwrap();
+    return (stand_in, url);
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("halls-fetch-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    return dir;
+}
+
+#[test]
+fn test_http_response_is_stored_with_etag() {
+    let (stand_in, url) = asset_serve(1);
+    let disk_cache = FetchDiskCache::new(cache_dir("store"), 1024);
+
+    let data = fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    )
+    .unwrap();
+    stand_in.handle.join().unwrap();
+
+    assert_eq!(data, BODY);
+    let entry = disk_cache.get(&url).unwrap();
+    assert_eq!(entry.data, BODY);
+    assert_eq!(entry.meta.etag.as_deref(), Some(ETAG));
+}
+
+#[test]
+fn test_cached_response_is_revalidated() {
+    let (stand_in, url) = asset_serve(2);
+    let disk_cache = FetchDiskCache::new(cache_dir("revalidate"), 1024);
+
+    fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    )
+    .unwrap();
+    let data = fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    )
+    .unwrap();
+    stand_in.handle.join().unwrap();
+
+    assert_eq!(data, BODY);
+    let requests = stand_in.headers.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].to_ascii_lowercase().contains("if-none-match"));
+    assert!(requests[1].to_ascii_lowercase().contains("if-none-match"));
+}
+
+#[test]
+fn test_cached_response_is_used_when_offline() {
+    let (stand_in, url) = asset_serve(1);
+    let disk_cache = FetchDiskCache::new(cache_dir("offline"), 1024);
+
+    fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    )
+    .unwrap();
+    stand_in.handle.join().unwrap();
+
+    let data = fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    )
+    .unwrap();
+    assert_eq!(data, BODY);
+}
+
+#[test]
+fn test_uncached_response_fails_when_offline() {
+    let (stand_in, url) = asset_serve(0);
+    let disk_cache = FetchDiskCache::new(cache_dir("uncached"), 1024);
+    stand_in.handle.join().unwrap();
+
+    let result = fetch_tracked(
+        &url,
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    );
+    assert!(matches!(result, Err(FetchError::HTTP)));
+}
+
+#[test]
+fn test_disk_cache_evicts_oldest_entries_over_cap() {
+    let disk_cache = FetchDiskCache::new(cache_dir("evict"), 16);
+    let first = Url::parse("https://example.com/first.png").unwrap();
+    let second = Url::parse("https://example.com/second.png").unwrap();
+    let meta = |url: &Url| FetchDiskCacheMeta {
+        url: url.clone(),
+        etag: None,
+        last_modified: None,
+    };
+
+    disk_cache.put(&meta(&first), &[0; 10]);
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    disk_cache.put(&meta(&second), &[0; 10]);
+
+    assert!(disk_cache.get(&first).is_none());
+    assert!(disk_cache.get(&second).is_some());
+}
+
+#[test]
+fn test_batch_collects_results_by_url() {
+    let dir = cache_dir("batch");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.bin"), b"a").unwrap();
+    std::fs::write(dir.join("b.bin"), b"b").unwrap();
+    let a = Url::from_file_path(dir.join("a.bin")).unwrap();
+    let b = Url::from_file_path(dir.join("b.bin")).unwrap();
+    let missing = Url::from_file_path(dir.join("missing.bin")).unwrap();
+    let unrequested = Url::from_file_path(dir.join("unrequested.bin")).unwrap();
+
+    let batch = FetchBatch::load(
+        vec![a.clone(), b.clone(), a.clone(), missing.clone()],
+        &FetchIntegrity::new(),
+        &FetchLimits::default(),
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadCancel::default(),
+        &LevelLoadProgress::default(),
+    );
+
+    assert_eq!(batch.get(&a).unwrap(), b"a");
+    assert_eq!(batch.get(&b).unwrap(), b"b");
+    assert!(matches!(batch.get(&missing), Err(FetchError::IO)));
+    assert!(matches!(
+        batch.get(&unrequested),
+        Err(FetchError::NotInBatch)
+    ));
+}
+
+struct StandInPool {
+    stand_in: StandIn,
+    urls: Vec<Url>,
+    concurrent_max: Arc<AtomicUsize>,
+}
+
+// Answers `count` paths with `body` after `delay`, each connection on its own thread, and
+// runs `on_request` as each request arrives.
+fn pool_serve(
+    body: &'static [u8],
+    count: usize,
+    delay: std::time::Duration,
+    on_request: impl Fn() + Send + Sync + 'static,
+) -> StandInPool {
+    let concurrent_max = Arc::new(AtomicUsize::new(0));
+    let concurrent = AtomicUsize::new(0);
+    let concurrent_max_clone = Arc::clone(&concurrent_max);
+    let paths: Vec<String> = (0..count).map(|ix| format!("{ix}.bin")).collect();
+    let stand_in = stand_in_serve_with(
+        paths.iter().map(|path| (path.clone(), body.to_vec())),
+        StandInOptions {
+            threaded: true,
+            respond: Some(Box::new(move |_, _| {
+                let now = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+                concurrent_max_clone.fetch_max(now, Ordering::SeqCst);
+                on_request();
+                thread::sleep(delay);
+                concurrent.fetch_sub(1, Ordering::SeqCst);
+                return false;
+            })),
+            ..StandInOptions::default()
+        },
+    );
+    let urls = paths
+        .iter()
+        .map(|path| stand_in.url.join(path).unwrap())
+        .collect();
+
+    return StandInPool {
+        stand_in,
+        urls,
+        concurrent_max,
+    };
+}
+
+#[test]
+fn test_batch_fetches_in_parallel_up_to_worker_count() {
+    let pool = pool_serve(
+        b"asset",
+        BATCH_WORKERS * 3,
+        std::time::Duration::from_millis(50),
+        || {},
+    );
+    let urls = pool.urls.clone();
+
+    let batch = FetchBatch::load(
+        urls.clone(),
+        &FetchIntegrity::new(),
+        &FetchLimits::default(),
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadCancel::default(),
+        &LevelLoadProgress::default(),
+    );
+
+    for url in &urls {
+        assert_eq!(batch.get(url).unwrap(), b"asset");
+    }
+    assert_eq!(pool.stand_in.requests.lock().unwrap().len(), urls.len());
+    let concurrent_max = pool.concurrent_max.load(Ordering::SeqCst);
+    assert!(concurrent_max > 1, "{concurrent_max}");
+    assert!(concurrent_max <= BATCH_WORKERS, "{concurrent_max}");
+}
+
+#[test]
+fn test_batch_stops_fetching_once_cancelled() {
+    let cancel = LevelLoadCancel::default();
+    let cancel_clone = cancel.clone();
+    let pool = pool_serve(
+        b"asset",
+        BATCH_WORKERS * 3,
+        std::time::Duration::from_millis(50),
+        move || {
+            cancel_clone.cancel();
+        },
+    );
+    let urls = pool.urls.clone();
+
+    let batch = FetchBatch::load(
+        urls.clone(),
+        &FetchIntegrity::new(),
+        &FetchLimits::default(),
+        None,
+        &FetchLevelBudget::default(),
+        &cancel,
+        &LevelLoadProgress::default(),
+    );
+
+    // Only fetches already under way when the first request arrived are finished.
+    assert!(pool.stand_in.requests.lock().unwrap().len() <= BATCH_WORKERS);
+    let skipped = urls
+        .iter()
+        .filter(|url| matches!(batch.get(url), Err(FetchError::NotInBatch)))
+        .count();
+    assert!(skipped >= urls.len() - BATCH_WORKERS, "{skipped}");
+}
+
+#[test]
+fn test_batch_over_level_limit_rejects_the_remainder() {
+    let pool = pool_serve(
+        b"0123456789",
+        BATCH_WORKERS * 2,
+        std::time::Duration::ZERO,
+        || {},
+    );
+    let urls = pool.urls.clone();
+    let limits = FetchLimits {
+        max_level_bytes: 55,
+        ..FetchLimits::default()
+    };
+
+    let batch = FetchBatch::load(
+        urls.clone(),
+        &FetchIntegrity::new(),
+        &limits,
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadCancel::default(),
+        &LevelLoadProgress::default(),
+    );
+
+    let accepted = urls.iter().filter(|url| batch.get(url).is_ok()).count();
+    let rejected = urls
+        .iter()
+        .filter(|url| matches!(batch.get(url), Err(FetchError::TooLarge)))
+        .count();
+    assert_eq!(accepted, 5);
+    assert_eq!(rejected, urls.len() - 5);
+}
+
+#[test]
+fn test_batch_reports_progress() {
+    let dir = cache_dir("batch-progress");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.bin"), b"aaa").unwrap();
+    std::fs::write(dir.join("b.bin"), b"bb").unwrap();
+    let a = Url::from_file_path(dir.join("a.bin")).unwrap();
+    let b = Url::from_file_path(dir.join("b.bin")).unwrap();
+    let progress = LevelLoadProgress::default();
+
+    FetchBatch::load(
+        vec![a, b],
+        &FetchIntegrity::new(),
+        &FetchLimits::default(),
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadCancel::default(),
+        &progress,
+    );
+
+    let snapshot = progress.snapshot();
+    assert_eq!(snapshot.assets_done, 2);
+    assert_eq!(snapshot.assets_total, 2);
+    assert_eq!(snapshot.bytes_received, 5);
+    assert_eq!(snapshot.bytes_expected, 5);
+    assert_eq!(snapshot.fraction(), 1.0);
+}
+
+#[test]
+fn test_response_reports_expected_bytes() {
+    let (stand_in, url) = asset_serve(1);
+    let disk_cache = FetchDiskCache::new(cache_dir("progress"), 1024);
+    let progress = LevelLoadProgress::default();
+
+    fetch_tracked(&url, &FetchLimits::default(), Some(&disk_cache), &progress).unwrap();
+    stand_in.handle.join().unwrap();
+
+    let snapshot = progress.snapshot();
+    assert_eq!(snapshot.bytes_expected, BODY.len() as u64);
+    assert_eq!(snapshot.bytes_received, BODY.len() as u64);
+    assert_eq!(snapshot.asset.as_deref(), Some("asset.bin"));
+}
+
+#[test]
+fn test_response_over_asset_limit_is_rejected() {
+    let (stand_in, url) = asset_serve(1);
+    let disk_cache = FetchDiskCache::new(cache_dir("too-large"), 1024);
+    let limits = FetchLimits {
+        max_asset_bytes: BODY.len() as u64 - 1,
+        ..FetchLimits::default()
+    };
+
+    let result = fetch_tracked(
+        &url,
+        &limits,
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    );
+    stand_in.handle.join().unwrap();
+
+    assert!(matches!(result, Err(FetchError::TooLarge)));
+    assert!(disk_cache.get(&url).is_none());
+}
+
+// Accepts one request and hands the connection to `answer` once its headers are read.
+fn answer_serve(answer: impl Fn(&mut TcpStream) + Send + Sync + 'static) -> StandIn {
+    return stand_in_serve_with(
+        Vec::<(String, Vec<u8>)>::new(),
+        StandInOptions {
+            connections: Some(1),
+            respond: Some(Box::new(move |_, stream| {
+                answer(stream);
+                return true;
+            })),
+            ..StandInOptions::default()
+        },
+    );
+}
+
+fn content_type_fetch(content_type: &'static str) -> Result<Vec<u8>, FetchError> {
+    let stand_in = answer_serve(move |stream| {
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+    });
+
+    let result = fetch_tracked(
+        &stand_in.url,
+        &FetchLimits::default(),
+        None,
+        &LevelLoadProgress::default(),
+    );
+    stand_in.handle.join().unwrap();
+    return result;
+}
+
+#[test]
+fn test_document_responses_are_rejected() {
+    for content_type in [
+        "text/html",
+        "text/HTML; charset=utf-8",
+        "application/xhtml+xml",
+        "application/xml",
+    ] {
+        let result = content_type_fetch(content_type);
+        assert!(
+            matches!(result, Err(FetchError::ContentType)),
+            "{content_type}"
+        );
+    }
+    assert!(content_type_fetch("model/gltf-binary").is_ok());
+    assert!(content_type_fetch("text/plain").is_ok());
+}
+
+#[test]
+fn test_trickling_response_times_out() {
+    let stand_in = answer_serve(|stream| {
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\n"
+        );
+        // Each byte arrives well within the read timeout, but the whole body does not
+        // arrive within the request timeout.
+        for _ in 0..100 {
+            if stream.write_all(b"x").is_err() {
+                return;
+            }
+            thread::sleep(std::time::Duration::from_millis(50));
+        }
+    });
+    let limits = FetchLimits {
+        request_timeout_secs: 1,
+        ..FetchLimits::default()
+    };
+
+    let result = fetch_tracked(&stand_in.url, &limits, None, &LevelLoadProgress::default());
+    stand_in.handle.join().unwrap();
+
+    assert!(matches!(result, Err(FetchError::Timeout)), "{result:?}");
+}
+
+#[test]
+fn test_batch_over_level_limit_is_rejected() {
+    let dir = cache_dir("batch-limit");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.bin"), [0; 8]).unwrap();
+    std::fs::write(dir.join("b.bin"), [0; 8]).unwrap();
+    let a = Url::from_file_path(dir.join("a.bin")).unwrap();
+    let b = Url::from_file_path(dir.join("b.bin")).unwrap();
+    let limits = FetchLimits {
+        max_level_bytes: 12,
+        ..FetchLimits::default()
+    };
+
+    let batch = FetchBatch::load(
+        vec![a.clone(), b.clone()],
+        &FetchIntegrity::new(),
+        &limits,
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadCancel::default(),
+        &LevelLoadProgress::default(),
+    );
+
+    let rejected = [batch.get(&a), batch.get(&b)]
+        .into_iter()
+        .filter(|result| matches!(result, Err(FetchError::TooLarge)))
+        .count();
+    assert_eq!(rejected, 1);
+}
+
+#[test]
+fn test_level_asset_shares_the_batch_budget() {
+    let dir = cache_dir("level-asset-budget");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("library.json"), [0; 8]).unwrap();
+    std::fs::write(dir.join("a.bin"), [0; 8]).unwrap();
+    let library = Url::from_file_path(dir.join("library.json")).unwrap();
+    let a = Url::from_file_path(dir.join("a.bin")).unwrap();
+    let limits = FetchLimits {
+        max_level_bytes: 12,
+        ..FetchLimits::default()
+    };
+    let budget = FetchLevelBudget::default();
+    let progress = LevelLoadProgress::default();
+
+    let result = fetch_level_asset(
+        &library,
+        &FetchIntegrity::new(),
+        &limits,
+        None,
+        &budget,
+        &progress,
+    );
+    assert!(result.is_ok());
+    let batch = FetchBatch::load(
+        vec![a.clone()],
+        &FetchIntegrity::new(),
+        &limits,
+        None,
+        &budget,
+        &LevelLoadCancel::default(),
+        &progress,
+    );
+
+    assert!(matches!(batch.get(&a), Err(FetchError::TooLarge)));
+}
+
+#[test]
+fn test_level_asset_mismatch_is_rejected() {
+    let dir = cache_dir("level-asset-mismatch");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("library.json"), b"tampered").unwrap();
+    let library = Url::from_file_path(dir.join("library.json")).unwrap();
+    let integrity = FetchIntegrity::from([(library.clone(), FetchDigest::of(b"original"))]);
+
+    let result = fetch_level_asset(
+        &library,
+        &integrity,
+        &FetchLimits::default(),
+        None,
+        &FetchLevelBudget::default(),
+        &LevelLoadProgress::default(),
+    );
+
+    assert!(matches!(result, Err(FetchError::Integrity)));
+}
+
+#[test]
+fn test_transient_errors() {
+    assert!(FetchError::Timeout.is_transient());
+    assert!(FetchError::Status(503).is_transient());
+    assert!(FetchError::Status(429).is_transient());
+    assert!(!FetchError::Status(404).is_transient());
+    assert!(!FetchError::TooLarge.is_transient());
+}
+
+fn bundle_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, data) in entries {
+        writer.start_file(*name, options).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    return writer.finish().unwrap().into_inner();
+}
+
+#[test]
+fn test_bundle_detection() {
+    let is_bundle = |url: &str| FetchBundle::is_bundle(&Url::parse(url).unwrap());
+    assert!(is_bundle("https://example.com/hall/level.zip"));
+    assert!(is_bundle("https://example.com/hall.HALLS?v=2"));
+    assert!(!is_bundle("https://example.com/hall/manifest.json"));
+}
+
+#[test]
+fn test_bundle_resolves_relative_hrefs() {
+    let data = bundle_bytes(&[("manifest.json", b"{}"), ("textures/wall.png", b"png")]);
+    let url = Url::parse("https://example.com/hall/level.zip").unwrap();
+    let bundle = FetchBundle::from_bytes(&url, &data, &FetchLimits::default()).unwrap();
+
+    assert_eq!(bundle.get(&bundle.manifest_url()).unwrap(), b"{}");
+    let texture = bundle.base_url().join("textures/wall.png").unwrap();
+    let missing = bundle.base_url().join("textures/floor.png").unwrap();
+    let batch = FetchBatch::from_bundle(
+        vec![texture.clone(), missing.clone()],
+        &FetchIntegrity::new(),
+        bundle,
+    );
+    assert_eq!(batch.get(&texture).unwrap(), b"png");
+    assert!(matches!(batch.get(&missing), Err(FetchError::NotInBundle)));
+}
+
+#[test]
+fn test_bundle_over_level_limit_is_rejected() {
+    let data = bundle_bytes(&[("a.bin", &[0; 8]), ("b.bin", &[0; 8])]);
+    let url = Url::parse("https://example.com/level.zip").unwrap();
+    let limits = FetchLimits {
+        max_level_bytes: 12,
+        ..FetchLimits::default()
+    };
+
+    let result = FetchBundle::from_bytes(&url, &data, &limits);
+
+    assert!(matches!(result, Err(FetchError::TooLarge)));
+}
+
+#[test]
+fn test_bundle_invalid_archive() {
+    let url = Url::parse("https://example.com/level.zip").unwrap();
+
+    let result = FetchBundle::from_bytes(&url, b"not a zip", &FetchLimits::default());
+
+    assert!(matches!(result, Err(FetchError::Archive)));
+}
+
+#[test]
+fn test_digest_hex_round_trip() {
+    let digest = FetchDigest::of(b"abc");
+    let hex = digest.to_hex();
+
+    assert_eq!(
+        hex,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(FetchDigest::from_hex(&hex), Some(digest));
+    assert_eq!(FetchDigest::from_hex("ba78"), None);
+    assert_eq!(FetchDigest::from_hex(&"zz".repeat(32)), None);
+}
+
+#[test]
+fn test_pinned_mismatch_is_rejected() {
+    let dir = cache_dir("pinned-mismatch");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.bin"), b"tampered").unwrap();
+    let url = Url::from_file_path(dir.join("a.bin")).unwrap();
+    let disk_cache = FetchDiskCache::new(dir.join("cache"), 1024);
+
+    let result = fetch_pinned(
+        &url,
+        FetchDigest::of(b"original"),
+        &FetchLimits::default(),
+        Some(&disk_cache),
+        &LevelLoadProgress::default(),
+    );
+
+    assert!(matches!(result, Err(FetchError::Integrity)));
+}
+
+#[test]
+fn test_pinned_content_is_shared_across_urls() {
+    let dir = cache_dir("pinned-shared");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.png"), b"wall").unwrap();
+    let a = Url::from_file_path(dir.join("a.png")).unwrap();
+    let b = Url::from_file_path(dir.join("elsewhere/b.png")).unwrap();
+    let disk_cache = FetchDiskCache::new(dir.join("cache"), 1024);
+    let digest = FetchDigest::of(b"wall");
+    let fetch = |url: &Url| {
+        return fetch_pinned(
+            url,
+            digest,
+            &FetchLimits::default(),
+            Some(&disk_cache),
+            &LevelLoadProgress::default(),
+        );
+    };
+
+    assert_eq!(fetch(&a).unwrap(), b"wall");
+    assert_eq!(fetch(&b).unwrap(), b"wall");
+}
+
+#[test]
+fn test_bundle_pinned_mismatch_is_rejected() {
+    let data = bundle_bytes(&[("wall.png", b"tampered")]);
+    let url = Url::parse("https://example.com/level.zip").unwrap();
+    let bundle = FetchBundle::from_bytes(&url, &data, &FetchLimits::default()).unwrap();
+    let wall = bundle.base_url().join("wall.png").unwrap();
+    let integrity = FetchIntegrity::from([(wall.clone(), FetchDigest::of(b"wall"))]);
+
+    let batch = FetchBatch::from_bundle(vec![wall.clone()], &integrity, bundle);
+
+    assert!(matches!(batch.get(&wall), Err(FetchError::Integrity)));
+}
@@ -1,4 +1,4 @@
-use glam::Vec3;
+use image::RgbaImage;
 use std::collections::HashMap;
 use url::Url;
 
@@ -7,52 +7,181 @@ use parry3d::query::{cast_shapes, ShapeCastHit, ShapeCastOptions};
 use parry3d::shape::Cuboid;
 
 use crate::audio::TrackData;
-use crate::gltf::{GLTFMesh, GLTFVertex};
+use crate::gltf::{GLTFMesh, GLTFMeshError, GLTFVertex};
 use crate::graphics::model::{Model, ModelUploadError, ModelVertex};
 
-use super::fetch::fetch;
+use super::cancel::LevelLoadCancel;
+use super::environment::{LevelEnvironment, LevelEnvironmentLoadError};
+use super::fetch::{
+    fetch_level_asset, FetchBatch, FetchBundle, FetchDigest, FetchDiskCache, FetchError,
+    FetchIntegrity, FetchLevelBudget, FetchLimits,
+};
 use super::manifest::{
-    LevelManifest, LevelManifestColliderType, LevelManifestMeta, LevelManifestSurface,
+    LevelManifest, LevelManifestCollider, LevelManifestColliderType, LevelManifestLibraryLoadError,
+    LevelManifestLoadError, LevelManifestMeta, LevelManifestPhysics, LevelManifestSpawn,
+    LevelManifestSurface, LevelManifestSurfaces,
 };
-use super::material::MaterialData as LevelMaterialData;
-use super::portal::LevelPortal;
+use super::material::{LevelMaterialLoadError, MaterialData as LevelMaterialData};
+use super::policy::{LevelPolicy, LevelPolicyError};
+use super::portal::{LevelPortal, LevelPortalLoadError};
+use super::progress::LevelLoadProgress;
 use super::render::LevelRenderParams;
 use super::state::{LevelColliderData, LevelState};
-use super::trimesh::trimesh_from_vertices;
+use super::trimesh::{trimesh_from_vertices, trimesh_size_bytes};
+
+const THUMBNAIL_SIZE: (u32, u32) = (160, 120);
 
 #[derive(Debug)]
 pub enum LevelMeshLoadError {
-    URLJoin,
-    Fetch,
-    GLTF,
+    URLJoin(String),
+    Fetch(String, FetchError),
+    GLTF(String, GLTFMeshError),
+}
+
+impl std::fmt::Display for LevelMeshLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelMeshLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelMeshLoadError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelMeshLoadError::GLTF(url, _) => write!(f, "failed to parse mesh {url}"),
+        };
+    }
+}
+
+impl std::error::Error for LevelMeshLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelMeshLoadError::URLJoin(_) => None,
+            LevelMeshLoadError::Fetch(_, err) => Some(err),
+            LevelMeshLoadError::GLTF(_, err) => Some(err),
+        };
+    }
 }
 
 #[derive(Debug)]
 pub enum LevelTrackLoadError {
-    URLJoin,
-    Fetch,
-    Decode,
+    URLJoin(String),
+    Fetch(String, FetchError),
+    Decode(String, rodio::decoder::DecoderError),
+}
+
+impl std::fmt::Display for LevelTrackLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelTrackLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelTrackLoadError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelTrackLoadError::Decode(url, _) => write!(f, "failed to decode audio {url}"),
+        };
+    }
+}
+
+impl std::error::Error for LevelTrackLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelTrackLoadError::URLJoin(_) => None,
+            LevelTrackLoadError::Fetch(_, err) => Some(err),
+            LevelTrackLoadError::Decode(_, err) => Some(err),
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelThumbnailLoadError {
+    URLJoin(String),
+    Fetch(String, FetchError),
+    Decode(String, image::ImageError),
+}
+
+impl std::fmt::Display for LevelThumbnailLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelThumbnailLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelThumbnailLoadError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelThumbnailLoadError::Decode(url, _) => write!(f, "failed to decode image {url}"),
+        };
+    }
+}
+
+impl std::error::Error for LevelThumbnailLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelThumbnailLoadError::URLJoin(_) => None,
+            LevelThumbnailLoadError::Fetch(_, err) => Some(err),
+            LevelThumbnailLoadError::Decode(_, err) => Some(err),
+        };
+    }
 }
 
 #[derive(Debug)]
 pub enum LevelLoadError {
-    Manifest,
-    Mesh,
-    Material,
-    Portal,
-    Track,
-    ModelUpload,
+    Manifest(LevelManifestLoadError),
+    SurfaceLibrary(LevelManifestLibraryLoadError),
+    Mesh(LevelMeshLoadError),
+    Collider(LevelMeshLoadError),
+    Material(LevelMaterialLoadError),
+    Portal(String, LevelPortalLoadError),
+    Track(LevelTrackLoadError),
+    Environment(LevelEnvironmentLoadError),
+    ModelUpload(ModelUploadError),
+    Policy(LevelPolicyError),
+    Cancelled,
+}
+
+impl LevelLoadError {
+    pub fn chain(&self) -> Vec<String> {
+        let mut chain = vec![self.to_string()];
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            chain.push(err.to_string());
+            source = err.source();
+        }
+        return chain;
+    }
+
+    pub fn is_transient(&self) -> bool {
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<FetchError>() {
+                return err.is_transient();
+            }
+            source = err.source();
+        }
+        return false;
+    }
 }
 
 impl std::fmt::Display for LevelLoadError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         return match self {
-            LevelLoadError::Manifest => write!(f, "failed to load level manifest"),
-            LevelLoadError::Mesh => write!(f, "failed to load level mesh"),
-            LevelLoadError::Material => write!(f, "failed to load level materials"),
-            LevelLoadError::Portal => write!(f, "failed to load level portals"),
-            LevelLoadError::Track => write!(f, "failed to load level track"),
-            LevelLoadError::ModelUpload => write!(f, "failed to upload level model"),
+            LevelLoadError::Manifest(_) => write!(f, "failed to load level manifest"),
+            LevelLoadError::SurfaceLibrary(_) => write!(f, "failed to load surface libraries"),
+            LevelLoadError::Mesh(_) => write!(f, "failed to load level mesh"),
+            LevelLoadError::Collider(_) => write!(f, "failed to load level collider"),
+            LevelLoadError::Material(_) => write!(f, "failed to load level materials"),
+            LevelLoadError::Portal(name, _) => write!(f, "failed to load portal \"{name}\""),
+            LevelLoadError::Track(_) => write!(f, "failed to load level track"),
+            LevelLoadError::Environment(_) => write!(f, "failed to load level environment"),
+            LevelLoadError::ModelUpload(_) => write!(f, "failed to upload level model"),
+            LevelLoadError::Policy(_) => write!(f, "blocked by level policy"),
+            LevelLoadError::Cancelled => write!(f, "level load cancelled"),
+        };
+    }
+}
+
+impl std::error::Error for LevelLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelLoadError::Manifest(err) => Some(err),
+            LevelLoadError::SurfaceLibrary(err) => Some(err),
+            LevelLoadError::Mesh(err) => Some(err),
+            LevelLoadError::Collider(err) => Some(err),
+            LevelLoadError::Material(err) => Some(err),
+            LevelLoadError::Portal(_, err) => Some(err),
+            LevelLoadError::Track(err) => Some(err),
+            LevelLoadError::Environment(err) => Some(err),
+            LevelLoadError::ModelUpload(err) => Some(err),
+            LevelLoadError::Policy(err) => Some(err),
+            LevelLoadError::Cancelled => None,
         };
     }
 }
@@ -67,6 +196,18 @@ pub struct LevelHit {
     pub kind: SurfaceKind,
 }
 
+pub struct LevelLoadParams<'a> {
+    pub limits: &'a FetchLimits,
+    pub disk_cache: Option<&'a FetchDiskCache>,
+    pub policy: &'a LevelPolicy,
+    // Refuse manifests and surface libraries with unknown keys rather than record them.
+    pub strict: bool,
+    pub cancel: &'a LevelLoadCancel,
+    pub progress: &'a LevelLoadProgress,
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+}
+
 pub struct Level {
     pub state: LevelState,
 }
@@ -84,15 +225,15 @@ impl Level {
     }
 
     fn surface_index_build<'a>(
-        manifest: &'a LevelManifest,
+        surfaces: &'a LevelManifestSurfaces,
         mesh: &GLTFMesh,
-    ) -> Vec<Option<&'a LevelManifestSurface>> {
-        let mut mapped: Vec<Option<&LevelManifestSurface>> =
+    ) -> Vec<Option<(&'a str, &'a Url, &'a LevelManifestSurface)>> {
+        let mut mapped: Vec<Option<(&str, &Url, &LevelManifestSurface)>> =
             Vec::with_capacity(mesh.materials().len());
 
         for material_name in mesh.materials() {
             let surface = match material_name {
-                Some(name) => manifest.level().surface(name),
+                Some(name) => surfaces.entry(name),
                 None => None,
             };
             mapped.push(surface);
@@ -100,19 +241,47 @@ impl Level {
         return mapped;
     }
 
-    fn mesh_load(base_url: &Url, mesh_href: &str) -> Result<GLTFMesh, LevelMeshLoadError> {
+    fn asset_urls(
+        base_url: &Url,
+        manifest: &LevelManifest,
+        surfaces: &LevelManifestSurfaces,
+    ) -> Vec<Url> {
+        let mut urls: Vec<Url> = manifest
+            .asset_hrefs()
+            .into_iter()
+            .filter_map(|href| base_url.join(href).ok())
+            .collect();
+        urls.extend(surfaces.asset_urls());
+        return urls;
+    }
+
+    fn integrity_build(base_url: &Url, manifest: &LevelManifest) -> FetchIntegrity {
+        return manifest
+            .integrity_iter()
+            .filter_map(|(href, digest)| Some((base_url.join(href).ok()?, *digest)))
+            .collect();
+    }
+
+    fn mesh_load(
+        base_url: &Url,
+        batch: &FetchBatch,
+        mesh_href: &str,
+    ) -> Result<GLTFMesh, LevelMeshLoadError> {
         let mesh_url = base_url
             .join(mesh_href)
-            .map_err(|_| LevelMeshLoadError::URLJoin)?;
-        let mesh_data = fetch(&mesh_url).map_err(|_| LevelMeshLoadError::Fetch)?;
-        return GLTFMesh::from_bytes(&mesh_data).map_err(|_| LevelMeshLoadError::GLTF);
+            .map_err(|_| LevelMeshLoadError::URLJoin(mesh_href.to_string()))?;
+        let mesh_data = batch
+            .get(&mesh_url)
+            .map_err(|err| LevelMeshLoadError::Fetch(mesh_url.to_string(), err))?;
+        return GLTFMesh::from_bytes(mesh_data)
+            .map_err(|err| LevelMeshLoadError::GLTF(mesh_url.to_string(), err));
     }
 
     fn model_build(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         mesh: &GLTFMesh,
-        surfaces: &[Option<&LevelManifestSurface>],
+        surfaces: &[Option<(&str, &Url, &LevelManifestSurface)>],
     ) -> Result<Model, ModelUploadError> {
         let mut vertices: Vec<ModelVertex> = Vec::new();
         for vertex in mesh.vertices() {
@@ -121,7 +290,7 @@ impl Level {
                 None => continue,
             };
             let surface = match surfaces.get(material_ix as usize) {
-                Some(Some(surface)) => surface,
+                Some(Some((_, _, surface))) => surface,
                 _ => continue,
             };
             if let LevelManifestSurface::Invisible { .. } = surface {
@@ -135,31 +304,51 @@ impl Level {
         return Ok(model);
     }
 
-    fn track_load(base_url: &Url, track_href: &str) -> Result<TrackData, LevelTrackLoadError> {
+    fn track_load(
+        base_url: &Url,
+        batch: &FetchBatch,
+        track_href: &str,
+    ) -> Result<TrackData, LevelTrackLoadError> {
         let track_url = base_url
             .join(track_href)
-            .map_err(|_| LevelTrackLoadError::URLJoin)?;
-        let track_data = fetch(&track_url).map_err(|_| LevelTrackLoadError::Fetch)?;
-        return TrackData::new(&track_data, true).map_err(|_| LevelTrackLoadError::Decode);
+            .map_err(|_| LevelTrackLoadError::URLJoin(track_href.to_string()))?;
+        let track_data = batch
+            .get(&track_url)
+            .map_err(|err| LevelTrackLoadError::Fetch(track_url.to_string(), err))?;
+        return TrackData::new(track_data, true)
+            .map_err(|err| LevelTrackLoadError::Decode(track_url.to_string(), err));
     }
 
+    // Scaled down to fit the level info panel, keeping its aspect ratio.
+    pub fn thumbnail_load(
+        base_url: &Url,
+        batch: &FetchBatch,
+        thumbnail_href: &str,
+    ) -> Result<RgbaImage, LevelThumbnailLoadError> {
+        let thumbnail_url = base_url
+            .join(thumbnail_href)
+            .map_err(|_| LevelThumbnailLoadError::URLJoin(thumbnail_href.to_string()))?;
+        let data = batch
+            .get(&thumbnail_url)
+            .map_err(|err| LevelThumbnailLoadError::Fetch(thumbnail_url.to_string(), err))?;
+        let image = image::load_from_memory(data)
+            .map_err(|err| LevelThumbnailLoadError::Decode(thumbnail_url.to_string(), err))?;
+        let (width, height) = THUMBNAIL_SIZE;
+        return Ok(image.thumbnail(width, height).to_rgba8());
+    }
+
+    // `collider_type` maps a vertex's material index to its collider, or `None` to skip it.
     fn collider_build(
         mesh: &GLTFMesh,
-        surfaces: &[Option<&LevelManifestSurface>],
+        collider_type: impl Fn(Option<u32>) -> Option<LevelManifestColliderType>,
     ) -> LevelColliderData {
         let mut wall_vertices: Vec<GLTFVertex> = Vec::new();
         let mut ladder_vertices: Vec<GLTFVertex> = Vec::new();
 
         for vertex in mesh.vertices() {
-            let material_ix = match vertex.material_ix {
-                Some(material_ix) => material_ix,
-                None => continue,
-            };
-            let surface = match surfaces.get(material_ix as usize) {
-                Some(Some(surface)) => surface,
-                _ => continue,
+            let Some(collider) = collider_type(vertex.material_ix) else {
+                continue;
             };
-            let collider = Self::surface_collider(surface);
 
             match collider {
                 LevelManifestColliderType::Wall => {
@@ -178,10 +367,25 @@ impl Level {
         };
     }
 
+    fn collider_mesh_build(mesh: &GLTFMesh, collider: &LevelManifestCollider) -> LevelColliderData {
+        let materials = mesh.materials();
+        return Self::collider_build(mesh, |material_ix| {
+            let material = material_ix
+                .and_then(|material_ix| materials.get(material_ix as usize))
+                .and_then(Option::as_deref);
+            return Some(collider.collider_type(material));
+        });
+    }
+
     pub fn url(&self) -> &Url {
         return &self.state.url;
     }
 
+    // The manifest followed by every asset it references.
+    pub fn source_urls(&self) -> &[Url] {
+        return &self.state.source_urls;
+    }
+
     pub fn meta(&self) -> &LevelManifestMeta {
         return &self.state.meta;
     }
@@ -241,10 +445,33 @@ impl Level {
         };
     }
 
+    // Approximate GPU and CPU footprint, used to budget the level cache.
+    pub fn size_bytes(&self) -> u64 {
+        let state = &self.state;
+        let portals: u64 = state.portals.values().map(LevelPortal::size_bytes).sum();
+        let track = state.track.as_ref().map_or(0, TrackData::size_bytes);
+        let thumbnail = state
+            .thumbnail
+            .as_ref()
+            .map_or(0, |image| image.len() as u64);
+        return state.model.size_bytes()
+            + state.material_data.size_bytes()
+            + trimesh_size_bytes(&state.collider_data.wall)
+            + trimesh_size_bytes(&state.collider_data.ladder)
+            + state.environment.sky_bytes
+            + portals
+            + track
+            + thumbnail;
+    }
+
     pub fn track(&self) -> Option<&TrackData> {
         return self.state.track.as_ref();
     }
 
+    pub fn thumbnail(&self) -> Option<&RgbaImage> {
+        return self.state.thumbnail.as_ref();
+    }
+
     pub fn portal(&self, name: &str) -> Option<&LevelPortal> {
         return self.state.portals.get(name);
     }
@@ -253,68 +480,224 @@ impl Level {
         return self.state.portals.iter();
     }
 
-    pub fn spawn_position(&self) -> Vec3 {
-        return self.state.spawn;
+    // The named spawn, falling back to the level spawn when there is no such name.
+    pub fn spawn(&self, name: Option<&str>) -> LevelManifestSpawn {
+        return name
+            .and_then(|name| self.state.spawns.get(name))
+            .copied()
+            .unwrap_or(self.state.spawn);
+    }
+
+    pub fn physics(&self) -> LevelManifestPhysics {
+        return self.state.physics;
+    }
+
+    // Manifest keys the load ignored, as JSON paths.
+    pub fn unknown_fields(&self) -> &[String] {
+        return &self.state.unknown_fields;
+    }
+
+    // Problems the load worked around, such as a thumbnail it could not decode.
+    pub fn warnings(&self) -> &[String] {
+        return &self.state.warnings;
     }
 
     pub fn render(&self, params: LevelRenderParams) {
         super::render::level_render(&self.state, params);
     }
 
-    pub fn load(
-        url: Url,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-    ) -> Result<Self, LevelLoadError> {
-        let manifest = LevelManifest::load(&url).map_err(|_| LevelLoadError::Manifest)?;
+    pub fn load(url: Url, params: LevelLoadParams) -> Result<Self, LevelLoadError> {
+        let LevelLoadParams {
+            limits,
+            disk_cache,
+            policy,
+            strict,
+            cancel,
+            progress,
+            device,
+            queue,
+        } = params;
+        policy.level_check(&url).map_err(LevelLoadError::Policy)?;
+
+        // Assets and surface libraries of a bundled level resolve inside the archive;
+        // portal links still resolve against the level URL.
+        let mut source_urls = vec![url.clone()];
+        let (base_url, manifest, surfaces, batch) = if FetchBundle::is_bundle(&url) {
+            let bundle = FetchBundle::load(&url, limits, disk_cache, progress)
+                .map_err(|err| LevelLoadError::Manifest(LevelManifestLoadError::Fetch(err)))?;
+            let manifest =
+                LevelManifest::load_bundle(&bundle, strict).map_err(LevelLoadError::Manifest)?;
+            let base_url = bundle.base_url().clone();
+            let integrity = Self::integrity_build(&base_url, &manifest);
+            let surfaces =
+                LevelManifestSurfaces::load(&manifest, &base_url, strict, |library_url| {
+                    let data = bundle.get(library_url)?;
+                    if let Some(digest) = integrity.get(library_url) {
+                        if FetchDigest::of(data) != *digest {
+                            return Err(Box::new(FetchError::Integrity));
+                        }
+                    }
+                    return Ok(data.to_vec());
+                })
+                .map_err(LevelLoadError::SurfaceLibrary)?;
+            let asset_urls = Self::asset_urls(&base_url, &manifest, &surfaces);
+            let batch = FetchBatch::from_bundle(asset_urls, &integrity, bundle);
+            (base_url, manifest, surfaces, batch)
+        } else {
+            let manifest = LevelManifest::load(&url, limits, disk_cache, strict)
+                .map_err(LevelLoadError::Manifest)?;
+            if cancel.is_cancelled() {
+                return Err(LevelLoadError::Cancelled);
+            }
+            // Libraries share the level's digests and byte budget with the batch that follows.
+            let integrity = Self::integrity_build(&url, &manifest);
+            let budget = FetchLevelBudget::default();
+            let surfaces = LevelManifestSurfaces::load(&manifest, &url, strict, |library_url| {
+                if cancel.is_cancelled() {
+                    return Err(Box::new(LevelLoadError::Cancelled));
+                }
+                policy.asset_check(&url, library_url)?;
+                progress.assets_add(1);
+                let result = fetch_level_asset(
+                    library_url,
+                    &integrity,
+                    limits,
+                    disk_cache,
+                    &budget,
+                    progress,
+                );
+                progress.asset_done();
+                return Ok(result?);
+            });
+            if cancel.is_cancelled() {
+                return Err(LevelLoadError::Cancelled);
+            }
+            let surfaces = surfaces.map_err(LevelLoadError::SurfaceLibrary)?;
+            source_urls.extend(surfaces.library_urls().iter().cloned());
+            let asset_urls = Self::asset_urls(&url, &manifest, &surfaces);
+            for asset_url in &asset_urls {
+                policy
+                    .asset_check(&url, asset_url)
+                    .map_err(LevelLoadError::Policy)?;
+            }
+            source_urls.extend(asset_urls.iter().cloned());
+            let batch = FetchBatch::load(
+                asset_urls, &integrity, limits, disk_cache, &budget, cancel, progress,
+            );
+            (url.clone(), manifest, surfaces, batch)
+        };
+        if cancel.is_cancelled() {
+            return Err(LevelLoadError::Cancelled);
+        }
+        for (_, portal) in manifest.portal_iter() {
+            if let Ok(link) = url.join(portal.link_href()) {
+                policy
+                    .link_check(&url, &link)
+                    .map_err(LevelLoadError::Policy)?;
+            }
+        }
 
-        let level_mesh =
-            Self::mesh_load(&url, manifest.level().mesh()).map_err(|_| LevelLoadError::Mesh)?;
-        let surface_index = Self::surface_index_build(&manifest, &level_mesh);
+        let level_mesh = Self::mesh_load(&base_url, &batch, manifest.level().mesh())
+            .map_err(LevelLoadError::Mesh)?;
+        let surface_index = Self::surface_index_build(&surfaces, &level_mesh);
 
         let material_data = LevelMaterialData::load(
             device,
             queue,
-            &url,
+            &base_url,
+            &batch,
             &surface_index,
             manifest.level().lightmap(),
         )
-        .map_err(|_| LevelLoadError::Material)?;
+        .map_err(LevelLoadError::Material)?;
         let model = Self::model_build(device, queue, &level_mesh, &surface_index)
-            .map_err(|_| LevelLoadError::ModelUpload)?;
+            .map_err(LevelLoadError::ModelUpload)?;
 
-        let collider_data = Self::collider_build(&level_mesh, &surface_index);
+        let collider_data = match manifest.level().collider() {
+            Some(collider) => {
+                let collider_mesh = Self::mesh_load(&base_url, &batch, collider.mesh())
+                    .map_err(LevelLoadError::Collider)?;
+                Self::collider_mesh_build(&collider_mesh, collider)
+            }
+            None => Self::collider_build(&level_mesh, |material_ix| {
+                match surface_index.get(material_ix? as usize) {
+                    Some(Some((_, _, surface))) => Some(Self::surface_collider(surface)),
+                    _ => None,
+                }
+            }),
+        };
 
         let mut portals = HashMap::new();
         for (name, manifest_portal) in manifest.portal_iter() {
             let portal = LevelPortal::load(
+                &base_url,
                 &url,
+                &batch,
                 manifest_portal.mesh(),
                 manifest_portal.link_href(),
                 device,
                 queue,
             )
-            .map_err(|_| LevelLoadError::Portal)?;
+            .map_err(|err| LevelLoadError::Portal(name.clone(), err))?;
             portals.insert(name.clone(), portal);
         }
 
+        let environment = LevelEnvironment::load(
+            device,
+            queue,
+            &base_url,
+            &batch,
+            manifest.level().environment(),
+        )
+        .map_err(LevelLoadError::Environment)?;
+
         let track = match manifest.level().track() {
-            Some(track_href) => {
-                Some(Self::track_load(&url, track_href).map_err(|_| LevelLoadError::Track)?)
-            }
+            Some(track_href) => Some(
+                Self::track_load(&base_url, &batch, track_href).map_err(LevelLoadError::Track)?,
+            ),
+            None => None,
+        };
+
+        // Only the pause menu shows the thumbnail, so a broken one is left out rather than
+        // failing the level.
+        let mut warnings = Vec::new();
+        let thumbnail = match manifest.meta().thumbnail() {
+            Some(thumbnail_href) => match Self::thumbnail_load(&base_url, &batch, thumbnail_href) {
+                Ok(thumbnail) => Some(thumbnail),
+                Err(err) => {
+                    log::warn!("level {url} has no thumbnail: {err}");
+                    warnings.push(format!("no thumbnail: {err}"));
+                    None
+                }
+            },
             None => None,
         };
 
         return Ok(Self {
             state: LevelState {
                 url,
+                source_urls,
                 meta: manifest.meta().clone(),
                 spawn: manifest.level().spawn(),
+                spawns: manifest
+                    .spawn_iter()
+                    .map(|(name, spawn)| (name.clone(), *spawn))
+                    .collect(),
+                physics: manifest.level().physics(),
+                unknown_fields: manifest
+                    .unknown_fields()
+                    .iter()
+                    .chain(surfaces.unknown_fields())
+                    .cloned()
+                    .collect(),
+                warnings,
+                environment,
                 collider_data,
                 model,
                 material_data,
                 portals,
                 track,
+                thumbnail,
             },
         });
     }
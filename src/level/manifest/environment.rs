@@ -0,0 +1,113 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::manifest::LevelManifestFromBytesError;
+use crate::color::Color;
+
+const FOG_START_MAX: f32 = 10000.0;
+const FOG_DENSITY_MAX: f32 = 10.0;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum LevelManifestFogMode {
+    Linear,
+    Exponential,
+}
+
+// Fog thickens with distance past `start`: linearly at `density` per unit until fully
+// fogged, or exponentially as `1 - exp(-density * distance)`. The color's alpha caps how
+// much of the scene the fog hides.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy)]
+#[schemars(deny_unknown_fields)]
+pub struct LevelManifestFog {
+    pub(super) mode: LevelManifestFogMode,
+    #[schemars(with = "[u8; 4]")]
+    pub(super) color: Color,
+    #[serde(default)]
+    #[schemars(range(min = 0.0, max = "FOG_START_MAX"))]
+    pub(super) start: f32,
+    #[schemars(range(min = 0.0, max = "FOG_DENSITY_MAX"))]
+    pub(super) density: f32,
+}
+
+impl LevelManifestFog {
+    pub fn mode(&self) -> LevelManifestFogMode {
+        return self.mode;
+    }
+
+    pub fn color(&self) -> Color {
+        return self.color;
+    }
+
+    pub fn start(&self) -> f32 {
+        return self.start;
+    }
+
+    pub fn density(&self) -> f32 {
+        return self.density;
+    }
+}
+
+// Cubemap faces are ordered +X, -X, +Y, -Y, +Z, -Z.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type")]
+#[schemars(deny_unknown_fields)]
+pub enum LevelManifestSky {
+    Equirectangular { image: String },
+    Cubemap { faces: [String; 6] },
+}
+
+impl LevelManifestSky {
+    pub fn hrefs(&self) -> Vec<&str> {
+        return match self {
+            LevelManifestSky::Equirectangular { image } => vec![image],
+            LevelManifestSky::Cubemap { faces } => faces.iter().map(String::as_str).collect(),
+        };
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default)]
+#[schemars(deny_unknown_fields)]
+pub struct LevelManifestEnvironment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<[u8; 4]>")]
+    pub(super) clear: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) fog: Option<LevelManifestFog>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) sky: Option<LevelManifestSky>,
+}
+
+impl LevelManifestEnvironment {
+    pub fn clear(&self) -> Color {
+        return self.clear.unwrap_or(Color::BLACK);
+    }
+
+    pub fn fog(&self) -> Option<&LevelManifestFog> {
+        return self.fog.as_ref();
+    }
+
+    pub fn sky(&self) -> Option<&LevelManifestSky> {
+        return self.sky.as_ref();
+    }
+
+    pub(super) fn range_check(&self) -> Result<(), LevelManifestFromBytesError> {
+        let Some(fog) = &self.fog else {
+            return Ok(());
+        };
+        if !(0.0..=FOG_START_MAX).contains(&fog.start) {
+            return Err(LevelManifestFromBytesError::FogOutOfRange(
+                "start",
+                0.0,
+                FOG_START_MAX,
+            ));
+        }
+        if !(0.0..=FOG_DENSITY_MAX).contains(&fog.density) {
+            return Err(LevelManifestFromBytesError::FogOutOfRange(
+                "density",
+                0.0,
+                FOG_DENSITY_MAX,
+            ));
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,244 @@
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use url::Url;
+
+use super::manifest::{
+    surfaces_check, LevelManifest, LevelManifestFromBytesError, LevelManifestSurface,
+};
+use super::migrate::decode;
+use super::unknown::unknown_fields;
+
+// A shared set of surfaces, imported by manifests through `level.surface_library`. A library
+// may import other libraries the same way, and its own surfaces override theirs. Unknown
+// top-level keys, such as `$schema`, are allowed.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct LevelManifestLibrary {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) surface_library: Vec<String>,
+    pub(super) surface: HashMap<String, LevelManifestSurface>,
+    // JSON paths of keys the decode ignored, filled in by `from_bytes`.
+    #[serde(skip)]
+    pub(super) unknown_fields: Vec<String>,
+}
+
+impl LevelManifestLibrary {
+    // A `strict` load fails on unknown keys instead of recording them.
+    pub fn from_bytes(data: &[u8], strict: bool) -> Result<Self, LevelManifestFromBytesError> {
+        let contents = std::str::from_utf8(data).map_err(|_| LevelManifestFromBytesError::UTF8)?;
+
+        let mut library: Self = decode(contents)?;
+        // Already decoded once, so this cannot fail.
+        let value: serde_json::Value = serde_json::from_str(contents).unwrap();
+        let unknown_fields = unknown_fields(&value, &schema_for!(LevelManifestLibrary));
+        if strict && !unknown_fields.is_empty() {
+            return Err(LevelManifestFromBytesError::UnknownFields(unknown_fields));
+        }
+        library.unknown_fields = unknown_fields;
+
+        surfaces_check(&library.surface)?;
+        return Ok(library);
+    }
+}
+
+// Whatever the caller's fetch failed with, such as a `FetchError` or a policy refusal.
+pub type LevelManifestLibraryFetchError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug)]
+pub enum LevelManifestLibraryLoadError {
+    URLJoin(String),
+    Fetch(String, LevelManifestLibraryFetchError),
+    FromBytes(String, LevelManifestFromBytesError),
+    // The import chain, starting and ending with the same library.
+    Cycle(Vec<String>),
+}
+
+impl std::fmt::Display for LevelManifestLibraryLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelManifestLibraryLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelManifestLibraryLoadError::Fetch(url, _) => {
+                write!(f, "failed to fetch surface library {url}")
+            }
+            LevelManifestLibraryLoadError::FromBytes(url, _) => {
+                write!(f, "invalid surface library {url}")
+            }
+            LevelManifestLibraryLoadError::Cycle(urls) => {
+                write!(
+                    f,
+                    "surface libraries import each other: {}",
+                    urls.join(" -> ")
+                )
+            }
+        };
+    }
+}
+
+impl std::error::Error for LevelManifestLibraryLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelManifestLibraryLoadError::Fetch(_, err) => Some(err.as_ref()),
+            LevelManifestLibraryLoadError::FromBytes(_, err) => Some(err),
+            LevelManifestLibraryLoadError::URLJoin(_) | LevelManifestLibraryLoadError::Cycle(_) => {
+                None
+            }
+        };
+    }
+}
+
+// Surfaces keyed by name, each with the URL its frame hrefs resolve against.
+type LevelManifestSurfaceMap = HashMap<String, (Url, LevelManifestSurface)>;
+
+struct LevelManifestLibraryResolve<F> {
+    get: F,
+    strict: bool,
+    // Each library's surfaces with its imports applied, so shared imports load once.
+    loaded: HashMap<Url, LevelManifestSurfaceMap>,
+    // Libraries being loaded, outermost first.
+    stack: Vec<Url>,
+    library_urls: Vec<Url>,
+    unknown_fields: Vec<String>,
+}
+
+impl<F> LevelManifestLibraryResolve<F>
+where
+    F: FnMut(&Url) -> Result<Vec<u8>, LevelManifestLibraryFetchError>,
+{
+    fn imports_load(
+        &mut self,
+        base_url: &Url,
+        hrefs: &[String],
+        surfaces: &mut LevelManifestSurfaceMap,
+    ) -> Result<(), LevelManifestLibraryLoadError> {
+        for href in hrefs {
+            let url = base_url
+                .join(href)
+                .map_err(|_| LevelManifestLibraryLoadError::URLJoin(href.clone()))?;
+            if let Some(start) = self.stack.iter().position(|ancestor| *ancestor == url) {
+                let mut chain: Vec<String> =
+                    self.stack[start..].iter().map(Url::to_string).collect();
+                chain.push(url.to_string());
+                return Err(LevelManifestLibraryLoadError::Cycle(chain));
+            }
+            let imported = self.library_load(url)?;
+            surfaces.extend(imported);
+        }
+        return Ok(());
+    }
+
+    fn library_load(
+        &mut self,
+        url: Url,
+    ) -> Result<LevelManifestSurfaceMap, LevelManifestLibraryLoadError> {
+        if let Some(surfaces) = self.loaded.get(&url) {
+            return Ok(surfaces.clone());
+        }
+
+        let data = (self.get)(&url)
+            .map_err(|err| LevelManifestLibraryLoadError::Fetch(url.to_string(), err))?;
+        let library = LevelManifestLibrary::from_bytes(&data, self.strict)
+            .map_err(|err| LevelManifestLibraryLoadError::FromBytes(url.to_string(), err))?;
+        self.unknown_fields.extend(
+            library
+                .unknown_fields
+                .iter()
+                .map(|path| format!("{path} in {url}")),
+        );
+
+        let mut surfaces = HashMap::new();
+        self.stack.push(url.clone());
+        self.imports_load(&url, &library.surface_library, &mut surfaces)?;
+        self.stack.pop();
+        surfaces.extend(
+            library
+                .surface
+                .into_iter()
+                .map(|(name, surface)| (name, (url.clone(), surface))),
+        );
+
+        self.library_urls.push(url.clone());
+        self.loaded.insert(url, surfaces.clone());
+        return Ok(surfaces);
+    }
+}
+
+// Every surface a level can use: its manifest's own, then those of its libraries.
+pub struct LevelManifestSurfaces {
+    surfaces: LevelManifestSurfaceMap,
+    library_urls: Vec<Url>,
+    unknown_fields: Vec<String>,
+}
+
+impl LevelManifestSurfaces {
+    // Only the manifest's own surfaces, without loading its libraries.
+    pub fn local(manifest: &LevelManifest, base_url: &Url) -> Self {
+        let surfaces = manifest
+            .level()
+            .surface_iter()
+            .map(|(name, surface)| (name.clone(), (base_url.clone(), surface.clone())))
+            .collect();
+        return Self {
+            surfaces,
+            library_urls: Vec::new(),
+            unknown_fields: Vec::new(),
+        };
+    }
+
+    // Libraries resolve relative to whatever imports them, and are read through `get` so
+    // the caller decides where they come from and which URLs are allowed.
+    pub fn load(
+        manifest: &LevelManifest,
+        base_url: &Url,
+        strict: bool,
+        get: impl FnMut(&Url) -> Result<Vec<u8>, LevelManifestLibraryFetchError>,
+    ) -> Result<Self, LevelManifestLibraryLoadError> {
+        let mut resolve = LevelManifestLibraryResolve {
+            get,
+            strict,
+            loaded: HashMap::new(),
+            stack: Vec::new(),
+            library_urls: Vec::new(),
+            unknown_fields: Vec::new(),
+        };
+        let mut surfaces = HashMap::new();
+        resolve.imports_load(base_url, manifest.level().surface_library(), &mut surfaces)?;
+        surfaces.extend(Self::local(manifest, base_url).surfaces);
+
+        return Ok(Self {
+            surfaces,
+            library_urls: resolve.library_urls,
+            unknown_fields: resolve.unknown_fields,
+        });
+    }
+
+    pub fn entry(&self, name: &str) -> Option<(&str, &Url, &LevelManifestSurface)> {
+        return self
+            .surfaces
+            .get_key_value(name)
+            .map(|(name, (base_url, surface))| (name.as_str(), base_url, surface));
+    }
+
+    // Frame URLs of every surface, resolved against the document that defined it.
+    pub fn asset_urls(&self) -> Vec<Url> {
+        return self
+            .surfaces
+            .values()
+            .flat_map(|(base_url, surface)| {
+                return surface
+                    .frame_hrefs()
+                    .iter()
+                    .filter_map(|href| base_url.join(href).ok());
+            })
+            .collect();
+    }
+
+    // Every library loaded, each after the libraries it imports.
+    pub fn library_urls(&self) -> &[Url] {
+        return &self.library_urls;
+    }
+
+    // Ignored keys in the libraries, as JSON paths followed by the library URL.
+    pub fn unknown_fields(&self) -> &[String] {
+        return &self.unknown_fields;
+    }
+}
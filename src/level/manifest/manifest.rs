@@ -1,39 +1,61 @@
-use glam::Vec3;
-use serde::Deserialize;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use url::Url;
 
+use super::environment::LevelManifestEnvironment;
+use super::migrate::LevelManifestVersioned;
+use super::physics::LevelManifestPhysics;
+use super::spawn::LevelManifestSpawn;
+use super::unknown::unknown_fields;
 use crate::color::Color;
-use crate::level::fetch::fetch;
+use crate::level::fetch::{
+    fetch, FetchBundle, FetchDigest, FetchDiskCache, FetchError, FetchLimits,
+};
 
-const MANIFEST_VERSION: &str = "coco";
+pub(super) const MANIFEST_VERSION: &str = "coco";
 const MAX_PORTALS: usize = 4;
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+fn manifest_version_schema(_: &mut SchemaGenerator) -> Schema {
+    let schema = SchemaObject {
+        const_value: Some(MANIFEST_VERSION.into()),
+        ..Default::default()
+    };
+    return schema.into();
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
 pub enum LevelManifestColliderType {
     Wall,
     Ladder,
     Null,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(tag = "type")]
+#[schemars(deny_unknown_fields)]
 pub enum LevelManifestSurface {
     TextureSingle {
         collider: Option<LevelManifestColliderType>,
         frame: String,
+        #[schemars(with = "Option<[u8; 4]>")]
         color: Option<Color>,
         unlit: Option<bool>,
     },
     TextureMulti {
         collider: Option<LevelManifestColliderType>,
+        #[schemars(length(min = 1))]
         frames: Vec<String>,
         animation_speed: f32,
+        #[schemars(with = "Option<[u8; 4]>")]
         color: Option<Color>,
         unlit: Option<bool>,
     },
     Untextured {
         collider: Option<LevelManifestColliderType>,
+        #[schemars(with = "[u8; 4]")]
         color: Color,
         unlit: Option<bool>,
     },
@@ -42,10 +64,59 @@ pub enum LevelManifestSurface {
     },
 }
 
-#[derive(Debug, Deserialize)]
+impl LevelManifestSurface {
+    pub fn frame_hrefs(&self) -> &[String] {
+        return match self {
+            LevelManifestSurface::TextureSingle { frame, .. } => std::slice::from_ref(frame),
+            LevelManifestSurface::TextureMulti { frames, .. } => frames,
+            LevelManifestSurface::Untextured { .. } | LevelManifestSurface::Invisible { .. } => &[],
+        };
+    }
+}
+
+pub(super) fn surfaces_check(
+    surfaces: &HashMap<String, LevelManifestSurface>,
+) -> Result<(), LevelManifestFromBytesError> {
+    for (name, surface) in surfaces {
+        if let LevelManifestSurface::TextureMulti { frames, .. } = surface {
+            if frames.is_empty() {
+                return Err(LevelManifestFromBytesError::EmptySurfaceFrameArray(
+                    name.clone(),
+                ));
+            }
+        }
+    }
+    return Ok(());
+}
+
+// A dedicated collision mesh. Collider types are looked up by the mesh's own material
+// names; materials without an entry are walls.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LevelManifestCollider {
+    pub(super) mesh: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(super) surface: HashMap<String, LevelManifestColliderType>,
+}
+
+impl LevelManifestCollider {
+    pub fn mesh(&self) -> &str {
+        return &self.mesh;
+    }
+
+    pub fn collider_type(&self, material: Option<&str>) -> LevelManifestColliderType {
+        return material
+            .and_then(|material| self.surface.get(material))
+            .copied()
+            .unwrap_or(LevelManifestColliderType::Wall);
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct LevelManifestPortal {
-    mesh: String,
-    link: String,
+    pub(super) mesh: String,
+    pub(super) link: String,
 }
 
 impl LevelManifestPortal {
@@ -58,20 +129,50 @@ impl LevelManifestPortal {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct LevelManifestLevel {
-    mesh: String,
-    lightmap: Option<String>,
-    track: Option<String>,
-    spawn: Option<Vec3>,
-    surface: HashMap<String, LevelManifestSurface>,
+    pub(super) mesh: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) collider: Option<LevelManifestCollider>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) lightmap: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) track: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) spawn: Option<LevelManifestSpawn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) physics: Option<LevelManifestPhysics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) environment: Option<LevelManifestEnvironment>,
+    // Library hrefs whose surfaces are imported, later ones overriding earlier ones.
+    // Surfaces below override them all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) surface_library: Vec<String>,
+    pub(super) surface: HashMap<String, LevelManifestSurface>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[schemars(deny_unknown_fields)]
 pub struct LevelManifestMeta {
-    name: String,
-    author: Option<String>,
-    track: Option<String>,
+    pub(super) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) author: Option<String>,
+    // Credited after `author`, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) contributors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) track: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) tags: Vec<String>,
+    // Free text, ideally an SPDX identifier such as `CC-BY-4.0`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) license: Option<String>,
+    // An image href, fetched with the level's assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) thumbnail: Option<String>,
 }
 
 impl LevelManifestMeta {
@@ -83,33 +184,129 @@ impl LevelManifestMeta {
         return self.author.as_deref();
     }
 
+    pub fn contributors(&self) -> &[String] {
+        return &self.contributors;
+    }
+
     pub fn track(&self) -> Option<&str> {
         return self.track.as_deref();
     }
+
+    pub fn description(&self) -> Option<&str> {
+        return self.description.as_deref();
+    }
+
+    pub fn tags(&self) -> &[String] {
+        return &self.tags;
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        return self.license.as_deref();
+    }
+
+    pub fn thumbnail(&self) -> Option<&str> {
+        return self.thumbnail.as_deref();
+    }
 }
 
-#[derive(Debug, Deserialize)]
+// The current manifest schema. Older schemas live in `migrate` and are upgraded into this
+// one by `from_bytes`. Unknown top-level keys, such as `$schema`, are allowed.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct LevelManifest {
     #[serde(rename = "_version")]
-    version: String,
-    meta: LevelManifestMeta,
-    level: LevelManifestLevel,
-    portal: HashMap<String, LevelManifestPortal>,
+    #[schemars(schema_with = "manifest_version_schema")]
+    pub(super) version: String,
+    pub(super) meta: LevelManifestMeta,
+    pub(super) level: LevelManifestLevel,
+    pub(super) portal: HashMap<String, LevelManifestPortal>,
+    // Named spawns, reached through `#spawn=<name>` URL fragments.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(super) spawn: HashMap<String, LevelManifestSpawn>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, String>")]
+    pub(super) integrity: HashMap<String, FetchDigest>,
+    // JSON paths of keys the decode ignored, filled in by `from_bytes`.
+    #[serde(skip)]
+    pub(super) unknown_fields: Vec<String>,
 }
 
 #[derive(Debug)]
 pub enum LevelManifestLoadError {
-    Fetch,
-    FromBytes,
+    Fetch(FetchError),
+    FromBytes(LevelManifestFromBytesError),
+}
+
+impl std::fmt::Display for LevelManifestLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelManifestLoadError::Fetch(_) => write!(f, "failed to fetch manifest"),
+            LevelManifestLoadError::FromBytes(_) => write!(f, "invalid manifest"),
+        };
+    }
+}
+
+impl std::error::Error for LevelManifestLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelManifestLoadError::Fetch(err) => Some(err),
+            LevelManifestLoadError::FromBytes(err) => Some(err),
+        };
+    }
 }
 
 #[derive(Debug)]
 pub enum LevelManifestFromBytesError {
     UTF8,
-    Decode,
+    // Carries the JSON path of the value that failed, e.g. `level.surface.Wall`.
+    Decode(serde_path_to_error::Error<serde_json::Error>),
     TooManyPortals,
     InvalidVersion,
-    EmptySurfaceFrameArray,
+    EmptySurfaceFrameArray(String),
+    // The field name and its allowed range.
+    PhysicsOutOfRange(&'static str, f32, f32),
+    FogOutOfRange(&'static str, f32, f32),
+    // JSON paths of the keys a strict load refused.
+    UnknownFields(Vec<String>),
+}
+
+impl std::fmt::Display for LevelManifestFromBytesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelManifestFromBytesError::UTF8 => write!(f, "manifest is not valid UTF-8"),
+            LevelManifestFromBytesError::Decode(_) => write!(f, "failed to decode manifest"),
+            LevelManifestFromBytesError::TooManyPortals => {
+                write!(f, "manifest has more than {} portals", MAX_PORTALS)
+            }
+            LevelManifestFromBytesError::InvalidVersion => {
+                write!(
+                    f,
+                    "manifest _version is unknown (current is \"{}\")",
+                    MANIFEST_VERSION
+                )
+            }
+            LevelManifestFromBytesError::EmptySurfaceFrameArray(name) => {
+                write!(f, "surface \"{name}\" has an empty frames array")
+            }
+            LevelManifestFromBytesError::PhysicsOutOfRange(name, min, max) => {
+                write!(f, "physics \"{name}\" must be between {min} and {max}")
+            }
+            LevelManifestFromBytesError::FogOutOfRange(name, min, max) => {
+                write!(f, "fog \"{name}\" must be between {min} and {max}")
+            }
+            LevelManifestFromBytesError::UnknownFields(paths) => {
+                write!(f, "unknown fields {}", paths.join(", "))
+            }
+        };
+    }
+}
+
+impl std::error::Error for LevelManifestFromBytesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelManifestFromBytesError::Decode(err) => Some(err),
+            _ => None,
+        };
+    }
 }
 
 impl LevelManifest {
@@ -130,39 +327,93 @@ impl LevelManifest {
         return self.portal.iter();
     }
 
+    pub fn spawn_iter(&self) -> impl Iterator<Item = (&String, &LevelManifestSpawn)> {
+        return self.spawn.iter();
+    }
+
     #[cfg(test)]
     pub fn portal_len(&self) -> usize {
         return self.portal.len();
     }
 
-    pub fn from_bytes(data: &[u8]) -> Result<Self, LevelManifestFromBytesError> {
-        let contents = std::str::from_utf8(data).map_err(|_| LevelManifestFromBytesError::UTF8)?;
+    // Every asset href the level may fetch, in load order.
+    pub fn asset_hrefs(&self) -> Vec<&str> {
+        let mut hrefs: Vec<&str> = vec![self.level.mesh()];
+        hrefs.extend(self.level.collider().map(LevelManifestCollider::mesh));
+        hrefs.extend(self.level.lightmap());
+        hrefs.extend(self.level.track());
+        hrefs.extend(self.meta.thumbnail());
+        if let Some(sky) = self
+            .level
+            .environment()
+            .and_then(LevelManifestEnvironment::sky)
+        {
+            hrefs.extend(sky.hrefs());
+        }
+        for (_, surface) in self.level.surface_iter() {
+            hrefs.extend(surface.frame_hrefs().iter().map(String::as_str));
+        }
+        for (_, portal) in self.portal_iter() {
+            hrefs.push(portal.mesh());
+        }
+        return hrefs;
+    }
+
+    // JSON paths of keys that were ignored, most likely typos.
+    pub fn unknown_fields(&self) -> &[String] {
+        return &self.unknown_fields;
+    }
 
-        let manifest: LevelManifest =
-            serde_json::from_str(contents).map_err(|_| LevelManifestFromBytesError::Decode)?;
+    // Pinned SHA-256 digests keyed by asset href, as written in the manifest.
+    pub fn integrity_iter(&self) -> impl Iterator<Item = (&String, &FetchDigest)> {
+        return self.integrity.iter();
+    }
 
-        if manifest.version != MANIFEST_VERSION {
-            return Err(LevelManifestFromBytesError::InvalidVersion);
+    // A `strict` load fails on unknown keys instead of recording them.
+    pub fn from_bytes(data: &[u8], strict: bool) -> Result<Self, LevelManifestFromBytesError> {
+        let contents = std::str::from_utf8(data).map_err(|_| LevelManifestFromBytesError::UTF8)?;
+
+        let versioned = LevelManifestVersioned::from_json(contents)?;
+        // Already decoded once, so this cannot fail.
+        let value: serde_json::Value = serde_json::from_str(contents).unwrap();
+        let unknown_fields = unknown_fields(&value, &versioned.schema());
+        if strict && !unknown_fields.is_empty() {
+            return Err(LevelManifestFromBytesError::UnknownFields(unknown_fields));
         }
+        let mut manifest = versioned.upgrade();
+        manifest.unknown_fields = unknown_fields;
 
         if manifest.portal.len() > MAX_PORTALS {
             return Err(LevelManifestFromBytesError::TooManyPortals);
         }
 
-        for (_, surface) in manifest.level.surface_iter() {
-            if let LevelManifestSurface::TextureMulti { frames, .. } = surface {
-                if frames.is_empty() {
-                    return Err(LevelManifestFromBytesError::EmptySurfaceFrameArray);
-                }
-            }
+        surfaces_check(&manifest.level.surface)?;
+
+        if let Some(physics) = manifest.level.physics {
+            physics.range_check()?;
+        }
+        if let Some(environment) = &manifest.level.environment {
+            environment.range_check()?;
         }
 
         return Ok(manifest);
     }
 
-    pub fn load(url: &Url) -> Result<Self, LevelManifestLoadError> {
-        let data = fetch(url).map_err(|_| LevelManifestLoadError::Fetch)?;
-        return Self::from_bytes(&data).map_err(|_| LevelManifestLoadError::FromBytes);
+    pub fn load(
+        url: &Url,
+        limits: &FetchLimits,
+        disk_cache: Option<&FetchDiskCache>,
+        strict: bool,
+    ) -> Result<Self, LevelManifestLoadError> {
+        let data = fetch(url, limits, disk_cache).map_err(LevelManifestLoadError::Fetch)?;
+        return Self::from_bytes(&data, strict).map_err(LevelManifestLoadError::FromBytes);
+    }
+
+    pub fn load_bundle(bundle: &FetchBundle, strict: bool) -> Result<Self, LevelManifestLoadError> {
+        let data = bundle
+            .get(&bundle.manifest_url())
+            .map_err(LevelManifestLoadError::Fetch)?;
+        return Self::from_bytes(data, strict).map_err(LevelManifestLoadError::FromBytes);
     }
 }
 
@@ -171,6 +422,19 @@ impl LevelManifestLevel {
         return &self.mesh;
     }
 
+    // When present, collision comes from this mesh instead of the render mesh.
+    pub fn collider(&self) -> Option<&LevelManifestCollider> {
+        return self.collider.as_ref();
+    }
+
+    pub fn physics(&self) -> LevelManifestPhysics {
+        return self.physics.unwrap_or_default();
+    }
+
+    pub fn environment(&self) -> Option<&LevelManifestEnvironment> {
+        return self.environment.as_ref();
+    }
+
     pub fn lightmap(&self) -> Option<&str> {
         return self.lightmap.as_deref();
     }
@@ -179,10 +443,15 @@ impl LevelManifestLevel {
         return self.track.as_deref();
     }
 
-    pub fn spawn(&self) -> Vec3 {
-        return self.spawn.unwrap_or(Vec3::ZERO);
+    pub fn spawn(&self) -> LevelManifestSpawn {
+        return self.spawn.unwrap_or_default();
+    }
+
+    pub fn surface_library(&self) -> &[String] {
+        return &self.surface_library;
     }
 
+    #[cfg(test)]
     pub fn surface(&self, name: &str) -> Option<&LevelManifestSurface> {
         return self.surface.get(name);
     }
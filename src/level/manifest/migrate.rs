@@ -0,0 +1,196 @@
+use glam::Vec3;
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::manifest::{
+    LevelManifest, LevelManifestCollider, LevelManifestColliderType, LevelManifestFromBytesError,
+    LevelManifestLevel, LevelManifestMeta, LevelManifestPortal, LevelManifestSurface,
+    MANIFEST_VERSION,
+};
+use super::spawn::LevelManifestSpawn;
+
+// Each published schema deserializes into its own struct and is upgraded one version at a
+// time until it reaches the current `LevelManifest`. Adding a version means freezing the
+// current struct here under its version name, giving it an `upgrade` to the new one, and
+// adding a variant below.
+pub enum LevelManifestVersioned {
+    // Manifests written before `_version` existed.
+    Unversioned(Box<LevelManifestUnversioned>),
+    Coco(Box<LevelManifest>),
+}
+
+#[derive(Deserialize)]
+struct LevelManifestVersionProbe {
+    #[serde(rename = "_version")]
+    version: Option<String>,
+}
+
+// Decodes `contents`, recording the JSON path of whatever failed to decode.
+pub(super) fn decode<T: DeserializeOwned>(
+    contents: &str,
+) -> Result<T, LevelManifestFromBytesError> {
+    let mut deserializer = serde_json::Deserializer::from_str(contents);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(LevelManifestFromBytesError::Decode)?;
+    deserializer.end().map_err(|err| {
+        let path = serde_path_to_error::Track::new().path();
+        return LevelManifestFromBytesError::Decode(serde_path_to_error::Error::new(path, err));
+    })?;
+    return Ok(value);
+}
+
+impl LevelManifestVersioned {
+    pub fn from_json(contents: &str) -> Result<Self, LevelManifestFromBytesError> {
+        let probe: LevelManifestVersionProbe = decode(contents)?;
+
+        return match probe.version.as_deref() {
+            None => decode(contents).map(LevelManifestVersioned::Unversioned),
+            Some(MANIFEST_VERSION) => decode(contents).map(LevelManifestVersioned::Coco),
+            Some(_) => Err(LevelManifestFromBytesError::InvalidVersion),
+        };
+    }
+
+    // The schema this version was decoded against, used to find the keys it ignored.
+    pub fn schema(&self) -> RootSchema {
+        return match self {
+            LevelManifestVersioned::Unversioned(_) => schema_for!(LevelManifestUnversioned),
+            LevelManifestVersioned::Coco(_) => schema_for!(LevelManifest),
+        };
+    }
+
+    pub fn upgrade(self) -> LevelManifest {
+        return match self {
+            LevelManifestVersioned::Unversioned(manifest) => manifest.upgrade(),
+            LevelManifestVersioned::Coco(manifest) => *manifest,
+        };
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum LevelManifestUnversionedColliderType {
+    Wall,
+    Ladder,
+    Null,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct LevelManifestUnversionedSurface {
+    image: Option<String>,
+    collider_type: Option<LevelManifestUnversionedColliderType>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct LevelManifestUnversionedPortal {
+    model: String,
+    link: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct LevelManifestUnversionedLevel {
+    model: String,
+    collider: Option<String>,
+    lightmap: Option<String>,
+    track: Option<String>,
+    #[schemars(with = "Option<[f32; 3]>")]
+    spawn: Option<Vec3>,
+    #[serde(default)]
+    surface: HashMap<String, LevelManifestUnversionedSurface>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LevelManifestUnversioned {
+    meta: LevelManifestMeta,
+    level: LevelManifestUnversionedLevel,
+    #[serde(default)]
+    portal: HashMap<String, LevelManifestUnversionedPortal>,
+}
+
+impl LevelManifestUnversionedColliderType {
+    fn upgrade(self) -> LevelManifestColliderType {
+        return match self {
+            LevelManifestUnversionedColliderType::Wall => LevelManifestColliderType::Wall,
+            LevelManifestUnversionedColliderType::Ladder => LevelManifestColliderType::Ladder,
+            LevelManifestUnversionedColliderType::Null => LevelManifestColliderType::Null,
+        };
+    }
+}
+
+impl LevelManifestUnversionedSurface {
+    fn upgrade(self) -> LevelManifestSurface {
+        let collider = self
+            .collider_type
+            .map(LevelManifestUnversionedColliderType::upgrade);
+        return match self.image {
+            Some(frame) => LevelManifestSurface::TextureSingle {
+                collider,
+                frame,
+                color: None,
+                unlit: None,
+            },
+            None => LevelManifestSurface::Invisible { collider },
+        };
+    }
+}
+
+impl LevelManifestUnversioned {
+    pub fn upgrade(self) -> LevelManifest {
+        // The collider mesh shared its material names, and so its collider types, with the
+        // surfaces of the render mesh.
+        let collider = self.level.collider.map(|mesh| LevelManifestCollider {
+            mesh,
+            surface: self
+                .level
+                .surface
+                .iter()
+                .filter_map(|(name, surface)| {
+                    let collider_type = surface.collider_type?.upgrade();
+                    return Some((name.clone(), collider_type));
+                })
+                .collect(),
+        });
+        let level = LevelManifestLevel {
+            mesh: self.level.model,
+            collider,
+            lightmap: self.level.lightmap,
+            track: self.level.track,
+            spawn: self.level.spawn.map(LevelManifestSpawn::from_position),
+            physics: None,
+            environment: None,
+            surface_library: Vec::new(),
+            surface: self
+                .level
+                .surface
+                .into_iter()
+                .map(|(name, surface)| (name, surface.upgrade()))
+                .collect(),
+        };
+        let portal = self
+            .portal
+            .into_iter()
+            .map(|(name, portal)| {
+                let portal = LevelManifestPortal {
+                    mesh: portal.model,
+                    link: portal.link,
+                };
+                return (name, portal);
+            })
+            .collect();
+
+        return LevelManifest {
+            version: MANIFEST_VERSION.to_string(),
+            meta: self.meta,
+            level,
+            portal,
+            spawn: HashMap::new(),
+            integrity: HashMap::new(),
+            unknown_fields: Vec::new(),
+        };
+    }
+}
@@ -1,11 +1,24 @@
+mod environment;
+mod library;
 mod manifest;
+mod migrate;
+mod physics;
+mod spawn;
+mod unknown;
 
 #[cfg(test)]
 mod test;
 
+pub use environment::{
+    LevelManifestEnvironment, LevelManifestFog, LevelManifestFogMode, LevelManifestSky,
+};
+pub use library::{LevelManifestLibraryLoadError, LevelManifestSurfaces};
 pub use manifest::{
-    LevelManifest, LevelManifestColliderType, LevelManifestMeta, LevelManifestSurface,
+    LevelManifest, LevelManifestCollider, LevelManifestColliderType, LevelManifestLoadError,
+    LevelManifestMeta, LevelManifestSurface,
 };
+pub use physics::LevelManifestPhysics;
+pub use spawn::LevelManifestSpawn;
 
 #[cfg(test)]
 pub use manifest::LevelManifestFromBytesError;
@@ -0,0 +1,101 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::manifest::LevelManifestFromBytesError;
+
+const SPEED_MIN: f32 = 0.5;
+const SPEED_MAX: f32 = 32.0;
+const GRAVITY_MIN: f32 = 1.0;
+const GRAVITY_MAX: f32 = 100.0;
+const JUMP_SPEED_MIN: f32 = 0.0;
+const JUMP_SPEED_MAX: f32 = 30.0;
+const STEP_HEIGHT_MIN: f32 = 0.0;
+const STEP_HEIGHT_MAX: f32 = 1.0;
+
+// Overrides for the player's movement constants while in this level. Omitted values keep
+// the game defaults.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq)]
+#[schemars(deny_unknown_fields)]
+pub struct LevelManifestPhysics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "SPEED_MIN", max = "SPEED_MAX"))]
+    speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "SPEED_MIN", max = "SPEED_MAX"))]
+    crouch_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "GRAVITY_MIN", max = "GRAVITY_MAX"))]
+    gravity: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "JUMP_SPEED_MIN", max = "JUMP_SPEED_MAX"))]
+    jump_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "SPEED_MIN", max = "SPEED_MAX"))]
+    ladder_climb_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = "STEP_HEIGHT_MIN", max = "STEP_HEIGHT_MAX"))]
+    step_height: Option<f32>,
+}
+
+impl LevelManifestPhysics {
+    pub fn speed(&self) -> Option<f32> {
+        return self.speed;
+    }
+
+    pub fn crouch_speed(&self) -> Option<f32> {
+        return self.crouch_speed;
+    }
+
+    pub fn gravity(&self) -> Option<f32> {
+        return self.gravity;
+    }
+
+    pub fn jump_speed(&self) -> Option<f32> {
+        return self.jump_speed;
+    }
+
+    pub fn ladder_climb_speed(&self) -> Option<f32> {
+        return self.ladder_climb_speed;
+    }
+
+    pub fn step_height(&self) -> Option<f32> {
+        return self.step_height;
+    }
+
+    // Shares its ranges with the `schemars` attributes above.
+    pub(super) fn range_check(&self) -> Result<(), LevelManifestFromBytesError> {
+        let values = [
+            ("speed", self.speed, SPEED_MIN, SPEED_MAX),
+            ("crouch_speed", self.crouch_speed, SPEED_MIN, SPEED_MAX),
+            ("gravity", self.gravity, GRAVITY_MIN, GRAVITY_MAX),
+            (
+                "jump_speed",
+                self.jump_speed,
+                JUMP_SPEED_MIN,
+                JUMP_SPEED_MAX,
+            ),
+            (
+                "ladder_climb_speed",
+                self.ladder_climb_speed,
+                SPEED_MIN,
+                SPEED_MAX,
+            ),
+            (
+                "step_height",
+                self.step_height,
+                STEP_HEIGHT_MIN,
+                STEP_HEIGHT_MAX,
+            ),
+        ];
+        for (name, value, min, max) in values {
+            if let Some(value) = value {
+                if !(min..=max).contains(&value) {
+                    return Err(LevelManifestFromBytesError::PhysicsOutOfRange(
+                        name, min, max,
+                    ));
+                }
+            }
+        }
+        return Ok(());
+    }
+}
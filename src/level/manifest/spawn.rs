@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use glam::{Vec2, Vec3};
+use percent_encoding::percent_decode_str;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use url::Url;
+
+const FRAGMENT_PREFIX: &str = "spawn=";
+
+// Where the player appears and which way they face. Written either as a bare position
+// `[x, y, z]` or as `{ "position": [x, y, z], "yaw": 90, "pitch": 0 }`. Angles are in
+// degrees: yaw turns counter-clockwise seen from above with 0 facing -Z, and positive
+// pitch looks up.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct LevelManifestSpawn {
+    position: Vec3,
+    yaw: f32,
+    pitch: f32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct LevelManifestSpawnOriented {
+    #[schemars(with = "[f32; 3]")]
+    position: Vec3,
+    #[serde(default)]
+    yaw: f32,
+    #[serde(default)]
+    pitch: f32,
+}
+
+struct LevelManifestSpawnVisitor;
+
+impl<'de> Visitor<'de> for LevelManifestSpawnVisitor {
+    type Value = LevelManifestSpawn;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return write!(f, "a position [x, y, z] or an object with a position");
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let position = Vec3::deserialize(SeqAccessDeserializer::new(seq))?;
+        return Ok(LevelManifestSpawn::from_position(position));
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        let spawn = LevelManifestSpawnOriented::deserialize(MapAccessDeserializer::new(map))?;
+        return Ok(LevelManifestSpawn {
+            position: spawn.position,
+            yaw: spawn.yaw,
+            pitch: spawn.pitch,
+        });
+    }
+}
+
+impl<'de> Deserialize<'de> for LevelManifestSpawn {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return deserializer.deserialize_any(LevelManifestSpawnVisitor);
+    }
+}
+
+impl JsonSchema for LevelManifestSpawn {
+    fn schema_name() -> String {
+        return "LevelManifestSpawn".to_string();
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let schema = SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    gen.subschema_for::<[f32; 3]>(),
+                    gen.subschema_for::<LevelManifestSpawnOriented>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        return schema.into();
+    }
+}
+
+impl Default for LevelManifestSpawn {
+    fn default() -> Self {
+        return Self::from_position(Vec3::ZERO);
+    }
+}
+
+impl LevelManifestSpawn {
+    pub fn from_position(position: Vec3) -> Self {
+        return Self {
+            position,
+            yaw: 0.0,
+            pitch: 0.0,
+        };
+    }
+
+    pub fn position(&self) -> Vec3 {
+        return self.position;
+    }
+
+    // Player rotation in radians, pitch in `x` and yaw in `y`.
+    pub fn rotation(&self) -> Vec2 {
+        return Vec2::new(self.pitch.to_radians(), self.yaw.to_radians());
+    }
+
+    // The spawn named by a `#spawn=<name>` URL fragment, if the URL has one. Names are
+    // percent-decoded, since URLs encode spaces and other characters in fragments.
+    pub fn fragment_name(url: &Url) -> Option<Cow<'_, str>> {
+        let name = url.fragment()?.strip_prefix(FRAGMENT_PREFIX)?;
+        return percent_decode_str(name).decode_utf8().ok();
+    }
+}
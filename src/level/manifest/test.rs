@@ -1,8 +1,9 @@
 use super::manifest::{LevelManifestColliderType, LevelManifestSurface};
 use super::*;
+use std::collections::HashMap;
 
 fn load_manifest_bytes(data: &[u8]) -> Result<LevelManifest, LevelManifestFromBytesError> {
-    return LevelManifest::from_bytes(data);
+    return LevelManifest::from_bytes(data, false);
 }
 
 fn load_manifest_json(json: &str) -> Result<LevelManifest, LevelManifestFromBytesError> {
@@ -40,7 +41,10 @@ fn test_valid_manifest_parsing() {
     assert_eq!(manifest.meta().track(), Some("Test Track"));
     assert_eq!(manifest.portal_len(), 1);
     assert!(manifest.portal("portal_a").is_some());
-    assert_eq!(manifest.level().spawn(), glam::Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        manifest.level().spawn().position(),
+        glam::Vec3::new(1.0, 2.0, 3.0)
+    );
 }
 
 #[test]
@@ -86,7 +90,10 @@ fn test_manifest_without_surface_is_rejected() {
     }"#;
 
     let result = load_manifest_json(json);
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
@@ -102,7 +109,10 @@ fn test_manifest_without_portals_is_rejected() {
     }"#;
 
     let result = load_manifest_json(json);
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
@@ -117,7 +127,10 @@ fn test_old_level_model_and_collider_fields_rejected() {
     }"#;
 
     let result = load_manifest_json(json);
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
@@ -136,13 +149,19 @@ fn test_old_portal_model_field_rejected() {
     }"#;
 
     let result = load_manifest_json(json);
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
 fn test_invalid_json_returns_decode_error() {
     let result = load_manifest_bytes(b"{ invalid json }");
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
@@ -214,7 +233,7 @@ fn test_spawn_missing_defaults_to_origin() {
     let result = load_manifest_json(json);
     assert!(result.is_ok());
     let manifest = result.unwrap();
-    assert_eq!(manifest.level().spawn(), glam::Vec3::new(0.0, 0.0, 0.0));
+    assert_eq!(manifest.level().spawn().position(), glam::Vec3::ZERO);
 }
 
 #[test]
@@ -237,7 +256,11 @@ fn test_spawn_accepts_coordinates() {
     let result = load_manifest_json(json);
     assert!(result.is_ok());
     let manifest = result.unwrap();
-    assert_eq!(manifest.level().spawn(), glam::Vec3::new(-12.5, 0.0, 3.75));
+    assert_eq!(
+        manifest.level().spawn().position(),
+        glam::Vec3::new(-12.5, 0.0, 3.75)
+    );
+    assert_eq!(manifest.level().spawn().rotation(), glam::Vec2::ZERO);
 }
 
 #[test]
@@ -477,7 +500,7 @@ fn test_surface_multi_texture_must_have_frames() {
     let result = load_manifest_json(json);
     assert!(matches!(
         result,
-        Err(LevelManifestFromBytesError::EmptySurfaceFrameArray)
+        Err(LevelManifestFromBytesError::EmptySurfaceFrameArray(ref name)) if name == "lava"
     ));
 }
 
@@ -500,7 +523,10 @@ fn test_old_surface_schema_is_rejected() {
     }"#;
 
     let result = load_manifest_json(json);
-    assert!(matches!(result, Err(LevelManifestFromBytesError::Decode)));
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::Decode(_))
+    ));
 }
 
 #[test]
@@ -539,3 +565,718 @@ fn test_manifest_load_accepts_reused_asset_paths() {
     assert_eq!(manifest.level().mesh(), "level.glb");
     assert_eq!(manifest.portal_len(), 2);
 }
+
+#[test]
+fn test_decode_error_reports_cause() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": { "spawn": [0.0, 0.0, 0.0], "surface": {} },
+        "portal": {}
+    }"#;
+
+    let err = load_manifest_json(json).unwrap_err();
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(err.to_string(), "failed to decode manifest");
+    assert!(source.to_string().contains("missing field `mesh`"));
+}
+
+#[test]
+fn test_unversioned_manifest_upgrades_model_fields() {
+    let json = r#"{
+        "meta": { "name": "Old Level" },
+        "level": {
+            "model": "level.glb",
+            "collider": "collider.glb",
+            "spawn": [1.0, 2.0, 3.0]
+        },
+        "portal": {
+            "p1": { "model": "p1.glb", "link": "a.json#x" }
+        }
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    assert_eq!(manifest.meta().name(), "Old Level");
+    assert_eq!(manifest.level().mesh(), "level.glb");
+    assert_eq!(
+        manifest.level().spawn().position(),
+        glam::Vec3::new(1.0, 2.0, 3.0)
+    );
+    let portal = manifest.portal("p1").unwrap();
+    assert_eq!(portal.mesh(), "p1.glb");
+    assert_eq!(portal.link_href(), "a.json#x");
+}
+
+#[test]
+fn test_unversioned_surface_upgrades_to_current_schema() {
+    let json = r#"{
+        "meta": { "name": "Old Level" },
+        "level": {
+            "model": "level.glb",
+            "surface": {
+                "wall": { "image": "wall.png", "collider_type": "wall" },
+                "ladder": { "collider_type": "ladder" }
+            }
+        }
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    match manifest.level().surface("wall").unwrap() {
+        LevelManifestSurface::TextureSingle {
+            collider, frame, ..
+        } => {
+            assert_eq!(frame, "wall.png");
+            assert_eq!(*collider, Some(LevelManifestColliderType::Wall));
+        }
+        _ => panic!("Expected TextureSingle"),
+    }
+    match manifest.level().surface("ladder").unwrap() {
+        LevelManifestSurface::Invisible { collider } => {
+            assert_eq!(*collider, Some(LevelManifestColliderType::Ladder));
+        }
+        _ => panic!("Expected Invisible"),
+    }
+}
+
+#[test]
+fn test_unversioned_manifest_with_current_fields_is_rejected() {
+    let json = r#"{
+        "meta": { "name": "Test Level" },
+        "level": { "mesh": "level.glb", "surface": {} },
+        "portal": {}
+    }"#;
+
+    let err = load_manifest_json(json).unwrap_err();
+    let source = std::error::Error::source(&err).unwrap();
+    assert!(source.to_string().contains("missing field `model`"));
+}
+
+#[test]
+fn test_upgraded_manifest_is_still_validated() {
+    let json = r#"{
+        "meta": { "name": "Old Level" },
+        "level": { "model": "level.glb" },
+        "portal": {
+            "p1": { "model": "p1.glb", "link": "a.json#x" },
+            "p2": { "model": "p2.glb", "link": "a.json#x" },
+            "p3": { "model": "p3.glb", "link": "a.json#x" },
+            "p4": { "model": "p4.glb", "link": "a.json#x" },
+            "p5": { "model": "p5.glb", "link": "a.json#x" }
+        }
+    }"#;
+
+    let result = load_manifest_json(json);
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::TooManyPortals)
+    ));
+}
+
+#[test]
+fn test_decode_error_reports_json_path() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": { "mesh": "level.glb", "spawn": "origin", "surface": {} },
+        "portal": {}
+    }"#;
+
+    let err = load_manifest_json(json).unwrap_err();
+    let LevelManifestFromBytesError::Decode(ref decode) = err else {
+        panic!("Expected Decode");
+    };
+    assert_eq!(decode.path().to_string(), "level.spawn");
+    let source = std::error::Error::source(&err).unwrap();
+    assert!(source.to_string().starts_with("level.spawn: invalid type"));
+}
+
+#[test]
+fn test_collider_mesh_parses_with_its_own_mapping() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": {
+            "mesh": "level.glb",
+            "collider": {
+                "mesh": "collider.glb",
+                "surface": { "Stairs": "Ladder", "Grass": "Null" }
+            },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let collider = manifest.level().collider().unwrap();
+    assert_eq!(collider.mesh(), "collider.glb");
+    assert_eq!(
+        collider.collider_type(Some("Stairs")),
+        LevelManifestColliderType::Ladder
+    );
+    assert_eq!(
+        collider.collider_type(Some("Grass")),
+        LevelManifestColliderType::Null
+    );
+    assert_eq!(
+        collider.collider_type(Some("Rock")),
+        LevelManifestColliderType::Wall
+    );
+    assert_eq!(
+        collider.collider_type(None),
+        LevelManifestColliderType::Wall
+    );
+    assert!(manifest.asset_hrefs().contains(&"collider.glb"));
+}
+
+#[test]
+fn test_collider_is_optional() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": { "mesh": "level.glb", "surface": {} },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    assert!(manifest.level().collider().is_none());
+}
+
+#[test]
+fn test_unversioned_collider_keeps_surface_collider_types() {
+    let json = r#"{
+        "meta": { "name": "Old Level" },
+        "level": {
+            "model": "level.glb",
+            "collider": "collider.glb",
+            "surface": {
+                "ladder": { "image": "ladder.png", "collider_type": "ladder" },
+                "trim": { "image": "trim.png" }
+            }
+        }
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let collider = manifest.level().collider().unwrap();
+    assert_eq!(collider.mesh(), "collider.glb");
+    assert_eq!(
+        collider.collider_type(Some("ladder")),
+        LevelManifestColliderType::Ladder
+    );
+    assert_eq!(
+        collider.collider_type(Some("trim")),
+        LevelManifestColliderType::Wall
+    );
+}
+
+#[test]
+fn test_spawn_accepts_position_with_yaw_and_pitch() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": {
+            "mesh": "level.glb",
+            "spawn": { "position": [1.0, 0.0, 2.0], "yaw": 90.0, "pitch": -45.0 },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let spawn = manifest.level().spawn();
+    assert_eq!(spawn.position(), glam::Vec3::new(1.0, 0.0, 2.0));
+    assert_eq!(
+        spawn.rotation(),
+        glam::Vec2::new(-45f32.to_radians(), 90f32.to_radians())
+    );
+}
+
+#[test]
+fn test_named_spawns_parse_in_either_form() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": { "mesh": "level.glb", "surface": {} },
+        "portal": {},
+        "spawn": {
+            "garden": { "position": [4.0, 0.0, 4.0], "yaw": 180.0 },
+            "roof": [0.0, 10.0, 0.0]
+        }
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let spawns: HashMap<&str, _> = manifest
+        .spawn_iter()
+        .map(|(name, spawn)| (name.as_str(), spawn))
+        .collect();
+    assert_eq!(spawns.len(), 2);
+    assert_eq!(spawns["garden"].rotation().y, 180f32.to_radians());
+    assert_eq!(spawns["roof"].position(), glam::Vec3::new(0.0, 10.0, 0.0));
+}
+
+#[test]
+fn test_spawn_object_requires_position() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Test Level" },
+        "level": { "mesh": "level.glb", "spawn": { "yaw": 90.0 }, "surface": {} },
+        "portal": {}
+    }"#;
+
+    let err = load_manifest_json(json).unwrap_err();
+    let LevelManifestFromBytesError::Decode(ref decode) = err else {
+        panic!("Expected Decode");
+    };
+    assert_eq!(decode.path().to_string(), "level.spawn");
+}
+
+#[test]
+fn test_spawn_fragment_name() {
+    let url = url::Url::parse("https://example.com/hall/manifest.json#spawn=garden").unwrap();
+    assert_eq!(
+        LevelManifestSpawn::fragment_name(&url).as_deref(),
+        Some("garden")
+    );
+
+    let url = url::Url::parse("https://example.com/hall/manifest.json#spawn=rose garden").unwrap();
+    assert_eq!(
+        LevelManifestSpawn::fragment_name(&url).as_deref(),
+        Some("rose garden")
+    );
+
+    let url = url::Url::parse("https://example.com/hall/manifest.json#spawn=caf%C3%A9").unwrap();
+    assert_eq!(
+        LevelManifestSpawn::fragment_name(&url).as_deref(),
+        Some("café")
+    );
+
+    let url = url::Url::parse("https://example.com/hall/manifest.json#door").unwrap();
+    assert_eq!(LevelManifestSpawn::fragment_name(&url), None);
+
+    let url = url::Url::parse("https://example.com/hall/manifest.json").unwrap();
+    assert_eq!(LevelManifestSpawn::fragment_name(&url), None);
+}
+
+#[test]
+fn test_physics_overrides_parse() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Moon" },
+        "level": {
+            "mesh": "level.glb",
+            "physics": { "gravity": 3.5, "jump_speed": 4.0 },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let physics = manifest.level().physics();
+    assert_eq!(physics.gravity(), Some(3.5));
+    assert_eq!(physics.jump_speed(), Some(4.0));
+    assert_eq!(physics.speed(), None);
+    assert_eq!(physics.step_height(), None);
+}
+
+#[test]
+fn test_physics_out_of_range_is_rejected() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Moon" },
+        "level": {
+            "mesh": "level.glb",
+            "physics": { "step_height": 2.0 },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let result = load_manifest_json(json);
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::PhysicsOutOfRange(
+            "step_height",
+            _,
+            _
+        ))
+    ));
+}
+
+#[test]
+fn test_physics_schema_ranges_match_range_check() {
+    let schema = serde_json::to_value(schemars::schema_for!(LevelManifestPhysics)).unwrap();
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(!properties.is_empty());
+
+    let check = |name: &str, value: f64| {
+        let physics: LevelManifestPhysics =
+            serde_json::from_value(serde_json::json!({ name: value })).unwrap();
+        return physics.range_check().is_ok();
+    };
+    for (name, property) in properties {
+        let min = property["minimum"].as_f64().unwrap();
+        let max = property["maximum"].as_f64().unwrap();
+        assert!(check(name, min), "{name} minimum {min}");
+        assert!(check(name, max), "{name} maximum {max}");
+        assert!(!check(name, min - 0.01), "{name} below {min}");
+        assert!(!check(name, max + 0.01), "{name} above {max}");
+    }
+}
+
+#[test]
+fn test_environment_parses_clear_fog_and_sky() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Mist" },
+        "level": {
+            "mesh": "level.glb",
+            "environment": {
+                "clear": [10, 20, 30, 255],
+                "fog": { "mode": "Exponential", "color": [200, 200, 200, 255], "density": 0.1 },
+                "sky": {
+                    "type": "Cubemap",
+                    "faces": ["px.png", "nx.png", "py.png", "ny.png", "pz.png", "nz.png"]
+                }
+            },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let environment = manifest.level().environment().unwrap();
+    assert_eq!(environment.clear().r, 10);
+    let fog = environment.fog().unwrap();
+    assert_eq!(fog.mode(), LevelManifestFogMode::Exponential);
+    assert_eq!(fog.start(), 0.0);
+    assert_eq!(fog.density(), 0.1);
+    assert!(manifest.asset_hrefs().contains(&"nz.png"));
+}
+
+#[test]
+fn test_environment_defaults_and_equirectangular_sky() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Dusk" },
+        "level": {
+            "mesh": "level.glb",
+            "environment": { "sky": { "type": "Equirectangular", "image": "sky.png" } },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let environment = manifest.level().environment().unwrap();
+    assert_eq!(environment.clear().r, 0);
+    assert!(environment.fog().is_none());
+    assert!(matches!(
+        environment.sky(),
+        Some(LevelManifestSky::Equirectangular { image }) if image == "sky.png"
+    ));
+    assert!(manifest.asset_hrefs().contains(&"sky.png"));
+}
+
+#[test]
+fn test_fog_out_of_range_is_rejected() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Mist" },
+        "level": {
+            "mesh": "level.glb",
+            "environment": {
+                "fog": { "mode": "Linear", "color": [0, 0, 0, 255], "density": 50.0 }
+            },
+            "surface": {}
+        },
+        "portal": {}
+    }"#;
+
+    let result = load_manifest_json(json);
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::FogOutOfRange("density", _, _))
+    ));
+}
+
+#[test]
+fn test_unknown_fields_are_recorded_with_their_paths() {
+    let json = r#"{
+        "$schema": "../manifest.schema.json",
+        "_version": "coco",
+        "meta": { "name": "Typos", "auther": "someone" },
+        "level": {
+            "mesh": "level.glb",
+            "lightmapp": "lightmap.png",
+            "spawn": { "position": [0.0, 0.0, 0.0], "yw": 90.0 },
+            "surface": {
+                "Wall": { "type": "TextureSingle", "frame": "wall.png", "unlitt": true },
+                "Glow": { "type": "TextureMulti", "frames": ["a.png"], "animaton_speed": 1.0, "animation_speed": 2.0 }
+            }
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    assert_eq!(
+        manifest.unknown_fields(),
+        [
+            "level.lightmapp",
+            "level.spawn.yw",
+            "level.surface.Glow.animaton_speed",
+            "level.surface.Wall.unlitt",
+            "meta.auther",
+        ]
+    );
+}
+
+#[test]
+fn test_known_fields_are_not_reported() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Tidy", "author": "someone" },
+        "level": {
+            "mesh": "level.glb",
+            "spawn": [1.0, 2.0, 3.0],
+            "physics": { "gravity": 10.0 },
+            "surface": {
+                "Wall": { "type": "Untextured", "color": [1, 2, 3, 255], "unlit": true },
+                "Hole": { "type": "Invisible", "collider": "Null" }
+            }
+        },
+        "portal": { "p1": { "mesh": "p1.glb", "link": "a.json#x" } }
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    assert!(manifest.unknown_fields().is_empty());
+}
+
+#[test]
+fn test_unversioned_unknown_fields_are_recorded() {
+    let json = r#"{
+        "meta": { "name": "Old Level" },
+        "level": {
+            "model": "level.glb",
+            "surface": { "Wall": { "image": "wall.png", "colider_type": "wall" } }
+        },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    assert_eq!(
+        manifest.unknown_fields(),
+        ["level.surface.Wall.colider_type"]
+    );
+}
+
+#[test]
+fn test_strict_load_rejects_unknown_fields() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": { "name": "Typos" },
+        "level": {
+            "mesh": "level.glb",
+            "surface": { "Wall": { "type": "TextureSingle", "frame": "wall.png", "unlitt": true } }
+        },
+        "portal": {}
+    }"#;
+
+    let result = LevelManifest::from_bytes(json.as_bytes(), true);
+    assert!(matches!(
+        result,
+        Err(LevelManifestFromBytesError::UnknownFields(paths)) if paths == ["level.surface.Wall.unlitt"]
+    ));
+}
+
+#[test]
+fn test_meta_parses_credits_license_tags_and_thumbnail() {
+    let json = r#"{
+        "_version": "coco",
+        "meta": {
+            "name": "Gallery",
+            "author": "Ada",
+            "contributors": ["Brook", "Cy"],
+            "description": "A quiet gallery.",
+            "tags": ["museum", "night"],
+            "license": "CC-BY-4.0",
+            "thumbnail": "thumbnail.png"
+        },
+        "level": { "mesh": "level.glb", "surface": {} },
+        "portal": {}
+    }"#;
+
+    let manifest = load_manifest_json(json).unwrap();
+    let meta = manifest.meta();
+    assert_eq!(meta.contributors(), ["Brook", "Cy"]);
+    assert_eq!(meta.description(), Some("A quiet gallery."));
+    assert_eq!(meta.tags(), ["museum", "night"]);
+    assert_eq!(meta.license(), Some("CC-BY-4.0"));
+    assert_eq!(meta.thumbnail(), Some("thumbnail.png"));
+    assert!(manifest.asset_hrefs().contains(&"thumbnail.png"));
+    assert!(manifest.unknown_fields().is_empty());
+}
+
+fn library_manifest_json(surface_library: &[&str], surface: &str) -> String {
+    return format!(
+        r#"{{
+            "_version": "coco",
+            "meta": {{ "name": "Library Hall" }},
+            "level": {{
+                "mesh": "level.glb",
+                "surface_library": {},
+                "surface": {surface}
+            }},
+            "portal": {{}}
+        }}"#,
+        serde_json::to_string(surface_library).unwrap()
+    );
+}
+
+fn surfaces_load(
+    manifest: &LevelManifest,
+    libraries: &[(&str, &str)],
+) -> Result<LevelManifestSurfaces, LevelManifestLibraryLoadError> {
+    let base_url = url::Url::parse("https://example.com/halls/a/manifest.json").unwrap();
+    let libraries: HashMap<url::Url, &str> = libraries
+        .iter()
+        .map(|(url, json)| (url::Url::parse(url).unwrap(), *json))
+        .collect();
+    return LevelManifestSurfaces::load(manifest, &base_url, false, |url| {
+        return match libraries.get(url) {
+            Some(json) => Ok(json.as_bytes().to_vec()),
+            None => Err(format!("no library at {url}").into()),
+        };
+    });
+}
+
+#[test]
+fn test_surface_library_frames_resolve_against_library_url() {
+    let json = library_manifest_json(
+        &["../shared/surfaces.json"],
+        r#"{ "Trim": { "type": "Untextured", "color": [0, 0, 0, 255] } }"#,
+    );
+    let library = r#"{
+        "surface": {
+            "Brick": { "type": "TextureSingle", "frame": "brick.png" },
+            "Trim": { "type": "TextureSingle", "frame": "trim.png" }
+        }
+    }"#;
+    let manifest = load_manifest_json(&json).unwrap();
+
+    let surfaces = surfaces_load(
+        &manifest,
+        &[("https://example.com/halls/shared/surfaces.json", library)],
+    )
+    .unwrap();
+
+    let (_, brick_url, _) = surfaces.entry("Brick").unwrap();
+    assert_eq!(
+        brick_url.join("brick.png").unwrap().as_str(),
+        "https://example.com/halls/shared/brick.png"
+    );
+    let (_, trim_url, trim) = surfaces.entry("Trim").unwrap();
+    assert_eq!(
+        trim_url.as_str(),
+        "https://example.com/halls/a/manifest.json"
+    );
+    assert!(matches!(trim, LevelManifestSurface::Untextured { .. }));
+    let asset_urls: Vec<String> = surfaces
+        .asset_urls()
+        .iter()
+        .map(|url| url.to_string())
+        .collect();
+    assert_eq!(asset_urls, ["https://example.com/halls/shared/brick.png"]);
+}
+
+#[test]
+fn test_surface_library_later_imports_override_earlier() {
+    let json = library_manifest_json(&["../shared/stone.json", "../shared/wood.json"], "{}");
+    let stone = r#"{ "surface": { "Floor": { "type": "TextureSingle", "frame": "stone.png" } } }"#;
+    let wood = r#"{
+        "surface_library": ["base/carpet.json"],
+        "surface": { "Floor": { "type": "TextureSingle", "frame": "wood.png" } }
+    }"#;
+    let carpet = r#"{ "surface": { "Rug": { "type": "TextureSingle", "frame": "rug.png" } } }"#;
+    let manifest = load_manifest_json(&json).unwrap();
+
+    let surfaces = surfaces_load(
+        &manifest,
+        &[
+            ("https://example.com/halls/shared/stone.json", stone),
+            ("https://example.com/halls/shared/wood.json", wood),
+            ("https://example.com/halls/shared/base/carpet.json", carpet),
+        ],
+    )
+    .unwrap();
+
+    let (_, _, floor) = surfaces.entry("Floor").unwrap();
+    assert_eq!(floor.frame_hrefs(), ["wood.png"]);
+    let (_, rug_url, _) = surfaces.entry("Rug").unwrap();
+    assert_eq!(
+        rug_url.as_str(),
+        "https://example.com/halls/shared/base/carpet.json"
+    );
+    let library_urls: Vec<&str> = surfaces
+        .library_urls()
+        .iter()
+        .map(|url| url.as_str())
+        .collect();
+    assert_eq!(
+        library_urls,
+        [
+            "https://example.com/halls/shared/stone.json",
+            "https://example.com/halls/shared/base/carpet.json",
+            "https://example.com/halls/shared/wood.json",
+        ]
+    );
+}
+
+#[test]
+fn test_surface_library_cycle_is_rejected() {
+    let json = library_manifest_json(&["../shared/a.json"], "{}");
+    let a = r#"{ "surface_library": ["b.json"], "surface": {} }"#;
+    let b = r#"{ "surface_library": ["a.json"], "surface": {} }"#;
+    let manifest = load_manifest_json(&json).unwrap();
+
+    let result = surfaces_load(
+        &manifest,
+        &[
+            ("https://example.com/halls/shared/a.json", a),
+            ("https://example.com/halls/shared/b.json", b),
+        ],
+    );
+
+    assert!(matches!(
+        result,
+        Err(LevelManifestLibraryLoadError::Cycle(urls)) if urls == [
+            "https://example.com/halls/shared/a.json",
+            "https://example.com/halls/shared/b.json",
+            "https://example.com/halls/shared/a.json",
+        ]
+    ));
+}
+
+#[test]
+fn test_surface_library_shared_import_is_not_a_cycle() {
+    let json = library_manifest_json(&["../shared/a.json", "../shared/b.json"], "{}");
+    let a = r#"{ "surface_library": ["common.json"], "surface": {} }"#;
+    let b = r#"{ "surface_library": ["common.json"], "surface": {} }"#;
+    let common = r#"{ "surface": { "Trim": { "type": "Invisible", "colider": "Wall" } } }"#;
+    let manifest = load_manifest_json(&json).unwrap();
+
+    let surfaces = surfaces_load(
+        &manifest,
+        &[
+            ("https://example.com/halls/shared/a.json", a),
+            ("https://example.com/halls/shared/b.json", b),
+            ("https://example.com/halls/shared/common.json", common),
+        ],
+    )
+    .unwrap();
+
+    assert!(surfaces.entry("Trim").is_some());
+    assert_eq!(surfaces.library_urls().len(), 3);
+    assert_eq!(
+        surfaces.unknown_fields(),
+        ["surface.Trim.colider in https://example.com/halls/shared/common.json"]
+    );
+}
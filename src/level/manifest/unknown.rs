@@ -0,0 +1,155 @@
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use schemars::Map;
+use serde_json::Value;
+
+// Finds keys in a manifest that its schema has no place for. Serde skips these silently,
+// so a typo like `"unlitt"` quietly falls back to the default. Only objects the schema
+// closes with `additionalProperties: false` are checked, which leaves top-level keys such
+// as `$schema` alone.
+struct UnknownFieldWalk<'a> {
+    definitions: &'a Map<String, Schema>,
+    paths: Vec<String>,
+}
+
+fn path_join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        return key.to_string();
+    }
+    return format!("{path}.{key}");
+}
+
+fn instance_type_matches(schema: &SchemaObject, value: &Value) -> bool {
+    let Some(instance_type) = &schema.instance_type else {
+        return true;
+    };
+    let types: &[InstanceType] = match instance_type {
+        SingleOrVec::Single(instance_type) => std::slice::from_ref(instance_type),
+        SingleOrVec::Vec(types) => types,
+    };
+    return types.iter().any(|instance_type| {
+        return match (instance_type, value) {
+            (InstanceType::Null, Value::Null)
+            | (InstanceType::Boolean, Value::Bool(_))
+            | (InstanceType::Number, Value::Number(_))
+            | (InstanceType::String, Value::String(_))
+            | (InstanceType::Array, Value::Array(_))
+            | (InstanceType::Object, Value::Object(_)) => true,
+            (InstanceType::Integer, Value::Number(number)) => !number.is_f64(),
+            _ => false,
+        };
+    });
+}
+
+// A property pinned to a single value, such as a surface's `type`, must match for the
+// schema to apply. This picks the variant of an internally tagged enum.
+fn tag_matches(schema: &SchemaObject, value: &Value) -> bool {
+    let (Some(object), Value::Object(map)) = (&schema.object, value) else {
+        return true;
+    };
+    return object.properties.iter().all(|(key, property)| {
+        let Schema::Object(SchemaObject {
+            enum_values: Some(values),
+            ..
+        }) = property
+        else {
+            return true;
+        };
+        let [tag] = values.as_slice() else {
+            return true;
+        };
+        return map.get(key).is_none_or(|value| value == tag);
+    });
+}
+
+impl<'a> UnknownFieldWalk<'a> {
+    fn resolve<'s>(&self, schema: &'s Schema) -> Option<&'s SchemaObject>
+    where
+        'a: 's,
+    {
+        let Schema::Object(object) = schema else {
+            return None;
+        };
+        let Some(reference) = &object.reference else {
+            return Some(object);
+        };
+        let name = reference.strip_prefix("#/definitions/")?;
+        return self.resolve(self.definitions.get(name)?);
+    }
+
+    fn walk<'s>(&mut self, value: &Value, schema: &'s Schema, path: &str)
+    where
+        'a: 's,
+    {
+        let Some(schema) = self.resolve(schema) else {
+            return;
+        };
+
+        if let Some(subschemas) = &schema.subschemas {
+            for subschema in subschemas.all_of.iter().flatten() {
+                self.walk(value, subschema, path);
+            }
+            let candidate = subschemas
+                .one_of
+                .iter()
+                .chain(subschemas.any_of.iter())
+                .flatten()
+                .find(|subschema| {
+                    return self.resolve(subschema).is_some_and(|subschema| {
+                        return instance_type_matches(subschema, value)
+                            && tag_matches(subschema, value);
+                    });
+                });
+            if let Some(candidate) = candidate {
+                self.walk(value, candidate, path);
+            }
+        }
+
+        match value {
+            Value::Object(map) => {
+                let Some(object) = &schema.object else {
+                    return;
+                };
+                for (key, value) in map {
+                    let path = path_join(path, key);
+                    if let Some(property) = object.properties.get(key) {
+                        self.walk(value, property, &path);
+                        continue;
+                    }
+                    match object.additional_properties.as_deref() {
+                        Some(Schema::Bool(false)) => self.paths.push(path),
+                        Some(additional) => self.walk(value, additional, &path),
+                        None => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let Some(array) = &schema.array else {
+                    return;
+                };
+                for (ix, item) in items.iter().enumerate() {
+                    let item_schema = match &array.items {
+                        Some(SingleOrVec::Single(schema)) => schema.as_ref(),
+                        Some(SingleOrVec::Vec(schemas)) => match schemas.get(ix) {
+                            Some(schema) => schema,
+                            None => continue,
+                        },
+                        None => continue,
+                    };
+                    self.walk(item, item_schema, &format!("{path}[{ix}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// JSON paths of the unknown keys in `value`, e.g. `level.surface.Wall.unlitt`.
+pub(super) fn unknown_fields(value: &Value, schema: &RootSchema) -> Vec<String> {
+    let mut walk = UnknownFieldWalk {
+        definitions: &schema.definitions,
+        paths: Vec::new(),
+    };
+    let root = Schema::Object(schema.schema.clone());
+    walk.walk(value, &root, "");
+    return walk.paths;
+}
@@ -3,12 +3,17 @@ use std::collections::HashMap;
 use url::Url;
 
 use crate::color::Color;
-use crate::graphics::pipeline::level::{PipelineLevelBindGroupTexture, TEXTURE_BUCKETS};
+use image::imageops::FilterType;
+
+use crate::graphics::pipeline::level::{
+    texture_bucket_alloc, texture_bucket_fit, PipelineLevelBindGroupTexture, TEXTURE_BUCKETS,
+};
 use crate::graphics::storage::{
-    MaterialIndexStorageBuffer, MaterialIndexStorageBufferData, MaterialTextureRef,
+    MaterialIndexStorageBuffer, MaterialIndexStorageBufferData,
+    MaterialIndexStorageBufferDataWriteError, MaterialTextureRef,
 };
-use crate::graphics::texture::TextureArray;
-use crate::level::fetch::fetch;
+use crate::graphics::texture::{TextureArray, TextureArrayWriteError};
+use crate::level::fetch::{FetchBatch, FetchError};
 
 use super::manifest::LevelManifestSurface;
 
@@ -18,69 +23,142 @@ pub struct MaterialData {
     pub texture_bind_group: PipelineLevelBindGroupTexture,
     pub material_index: MaterialIndexStorageBuffer,
     pub lightmap_material_id: u32,
+    pub texture_bytes: u64,
 }
 
 #[derive(Debug)]
 pub enum LevelMaterialLoadImageError {
-    URLJoin,
-    Fetch,
+    URLJoin(String),
+    Fetch(String, FetchError),
+    Decode(String, image::ImageError),
+    // The image URL and the size of the bucket it needed a layer in.
+    TextureLimit(String, u32, u32),
+    TextureArrayWrite(String, TextureArrayWriteError),
+}
+
+impl std::fmt::Display for LevelMaterialLoadImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelMaterialLoadImageError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelMaterialLoadImageError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelMaterialLoadImageError::Decode(url, _) => {
+                write!(f, "failed to decode image {url}")
+            }
+            LevelMaterialLoadImageError::TextureLimit(url, w, h) => {
+                write!(
+                    f,
+                    "image {url} needs a {w}x{h} texture, but every {w}x{h} and larger texture \
+                     slot is taken"
+                )
+            }
+            LevelMaterialLoadImageError::TextureArrayWrite(url, _) => {
+                write!(f, "failed to upload image {url}")
+            }
+        };
+    }
+}
+
+impl std::error::Error for LevelMaterialLoadImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelMaterialLoadImageError::Fetch(_, err) => Some(err),
+            LevelMaterialLoadImageError::Decode(_, err) => Some(err),
+            LevelMaterialLoadImageError::TextureArrayWrite(_, err) => Some(err),
+            LevelMaterialLoadImageError::URLJoin(_)
+            | LevelMaterialLoadImageError::TextureLimit(..) => None,
+        };
+    }
 }
 
 #[derive(Debug)]
 pub enum LevelMaterialLoadError {
-    Image,
-    TextureArrayWrite,
-    MaterialIndex,
+    Surface(String, LevelMaterialLoadImageError),
+    Lightmap(LevelMaterialLoadImageError),
+    MaterialIndex(MaterialIndexStorageBufferDataWriteError),
 }
 
-fn find_texture_bucket(w: u32, h: u32) -> Option<usize> {
-    return TEXTURE_BUCKETS
-        .iter()
-        .position(|b| b.width == w && b.height == h);
+impl std::fmt::Display for LevelMaterialLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelMaterialLoadError::Surface(name, _) => {
+                write!(f, "failed to load surface \"{name}\"")
+            }
+            LevelMaterialLoadError::Lightmap(_) => write!(f, "failed to load lightmap"),
+            LevelMaterialLoadError::MaterialIndex(_) => {
+                write!(f, "failed to build material index")
+            }
+        };
+    }
 }
 
-fn load_image(base_url: &Url, href: &str) -> Result<Vec<u8>, LevelMaterialLoadImageError> {
-    let url = base_url
-        .join(href)
-        .map_err(|_| LevelMaterialLoadImageError::URLJoin)?;
-    return fetch(&url).map_err(|_| LevelMaterialLoadImageError::Fetch);
+impl std::error::Error for LevelMaterialLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelMaterialLoadError::Surface(_, err) => Some(err),
+            LevelMaterialLoadError::Lightmap(err) => Some(err),
+            LevelMaterialLoadError::MaterialIndex(err) => Some(err),
+        };
+    }
+}
+
+fn load_image(
+    batch: &FetchBatch,
+    url: &Url,
+) -> Result<image::RgbaImage, LevelMaterialLoadImageError> {
+    let data = batch
+        .get(url)
+        .map_err(|err| LevelMaterialLoadImageError::Fetch(url.to_string(), err))?;
+    let img = image::load_from_memory(data)
+        .map_err(|err| LevelMaterialLoadImageError::Decode(url.to_string(), err))?;
+    return Ok(img.to_rgba8());
 }
 
 fn surface_frame_refs_load(
     queue: &wgpu::Queue,
     base_url: &Url,
+    batch: &FetchBatch,
     frame_paths: &[String],
     diffuse: &mut [TextureArray],
     next_free: &mut [usize; TEXTURE_BUCKETS.len()],
-    texture_ref_cache: &mut HashMap<String, MaterialTextureRef>,
-) -> Result<Vec<MaterialTextureRef>, LevelMaterialLoadError> {
+    texture_ref_cache: &mut HashMap<Url, MaterialTextureRef>,
+) -> Result<Vec<MaterialTextureRef>, LevelMaterialLoadImageError> {
     let mut frames: Vec<MaterialTextureRef> = Vec::with_capacity(frame_paths.len());
 
     for frame_path in frame_paths {
-        if let Some(&cached_ref) = texture_ref_cache.get(frame_path) {
+        let url = base_url
+            .join(frame_path)
+            .map_err(|_| LevelMaterialLoadImageError::URLJoin(frame_path.clone()))?;
+        if let Some(&cached_ref) = texture_ref_cache.get(&url) {
             frames.push(cached_ref);
             continue;
         }
 
-        let frame_data =
-            load_image(base_url, frame_path).map_err(|_| LevelMaterialLoadError::Image)?;
-        let img = image::load_from_memory(&frame_data)
-            .map_err(|_| LevelMaterialLoadError::Image)?
-            .to_rgba8();
+        let mut img = load_image(batch, &url)?;
         let (w, h) = img.dimensions();
 
-        let bucket_ix = find_texture_bucket(w, h).ok_or(LevelMaterialLoadError::Image)?;
-        let layer = next_free[bucket_ix];
+        let fit_ix = texture_bucket_fit(w, h);
+        let (bucket_ix, layer) = texture_bucket_alloc(next_free, fit_ix).ok_or_else(|| {
+            let bucket = TEXTURE_BUCKETS[fit_ix];
+            return LevelMaterialLoadImageError::TextureLimit(
+                url.to_string(),
+                bucket.width,
+                bucket.height,
+            );
+        })?;
+        // UVs span the whole image, so stretching it to the layer's size keeps the mapping.
+        let bucket = TEXTURE_BUCKETS[bucket_ix];
+        if (w, h) != (bucket.width, bucket.height) {
+            img = image::imageops::resize(&img, bucket.width, bucket.height, FilterType::Triangle);
+        }
         diffuse[bucket_ix]
             .write(queue, layer, &img)
-            .map_err(|_| LevelMaterialLoadError::TextureArrayWrite)?;
-        next_free[bucket_ix] += 1;
+            .map_err(|err| LevelMaterialLoadImageError::TextureArrayWrite(url.to_string(), err))?;
 
         let texture_ref = MaterialTextureRef {
             bucket: bucket_ix as u16,
             layer: layer as u16,
         };
-        texture_ref_cache.insert(frame_path.clone(), texture_ref);
+        texture_ref_cache.insert(url, texture_ref);
         frames.push(texture_ref);
     }
 
@@ -88,21 +166,28 @@ fn surface_frame_refs_load(
 }
 
 impl MaterialData {
+    pub fn size_bytes(&self) -> u64 {
+        return self.texture_bytes + self.material_index.size_bytes();
+    }
+
+    // Surface frames resolve against the URL given with each surface, which differs from
+    // `base_url` for surfaces imported from a library.
     pub fn load(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         base_url: &Url,
-        surfaces: &[Option<&LevelManifestSurface>],
+        batch: &FetchBatch,
+        surfaces: &[Option<(&str, &Url, &LevelManifestSurface)>],
         lightmap_path: Option<&str>,
     ) -> Result<Self, LevelMaterialLoadError> {
         let mut diffuse =
             TEXTURE_BUCKETS.map(|b| TextureArray::new(device, (b.width, b.height), b.layers));
         let mut material_index_data = MaterialIndexStorageBufferData::new();
         let mut next_free: [usize; TEXTURE_BUCKETS.len()] = [0; TEXTURE_BUCKETS.len()];
-        let mut texture_ref_cache: HashMap<String, MaterialTextureRef> = HashMap::new();
+        let mut texture_ref_cache: HashMap<Url, MaterialTextureRef> = HashMap::new();
 
         for (ix, surface) in surfaces.iter().enumerate() {
-            let surface = match surface {
+            let (name, surface_url, surface) = match surface {
                 Some(surface) => *surface,
                 None => continue,
             };
@@ -118,16 +203,18 @@ impl MaterialData {
                     let frame_paths = std::slice::from_ref::<String>(frame);
                     let frames = surface_frame_refs_load(
                         queue,
-                        base_url,
+                        surface_url,
+                        batch,
                         frame_paths,
                         &mut diffuse,
                         &mut next_free,
                         &mut texture_ref_cache,
-                    )?;
+                    )
+                    .map_err(|err| LevelMaterialLoadError::Surface(name.to_string(), err))?;
 
                     material_index_data
                         .write(ix as u32, STATIC_ANIMATION_SPEED, &frames, color, unlit)
-                        .map_err(|_| LevelMaterialLoadError::MaterialIndex)?;
+                        .map_err(LevelMaterialLoadError::MaterialIndex)?;
                 }
                 LevelManifestSurface::TextureMulti {
                     frames: frame_paths,
@@ -140,22 +227,24 @@ impl MaterialData {
                     let unlit = (*unlit).unwrap_or(false);
                     let frames = surface_frame_refs_load(
                         queue,
-                        base_url,
+                        surface_url,
+                        batch,
                         frame_paths,
                         &mut diffuse,
                         &mut next_free,
                         &mut texture_ref_cache,
-                    )?;
+                    )
+                    .map_err(|err| LevelMaterialLoadError::Surface(name.to_string(), err))?;
 
                     material_index_data
                         .write(ix as u32, *animation_speed, &frames, color, unlit)
-                        .map_err(|_| LevelMaterialLoadError::MaterialIndex)?;
+                        .map_err(LevelMaterialLoadError::MaterialIndex)?;
                 }
                 LevelManifestSurface::Untextured { color, unlit, .. } => {
                     let unlit = (*unlit).unwrap_or(false);
                     material_index_data
                         .write(ix as u32, STATIC_ANIMATION_SPEED, &[], *color, unlit)
-                        .map_err(|_| LevelMaterialLoadError::MaterialIndex)?;
+                        .map_err(LevelMaterialLoadError::MaterialIndex)?;
                 }
                 LevelManifestSurface::Invisible { .. } => {}
             }
@@ -169,11 +258,13 @@ impl MaterialData {
                 let frames = surface_frame_refs_load(
                     queue,
                     base_url,
+                    batch,
                     frame_paths,
                     &mut diffuse,
                     &mut next_free,
                     &mut texture_ref_cache,
-                )?;
+                )
+                .map_err(LevelMaterialLoadError::Lightmap)?;
                 material_index_data
                     .write(
                         lightmap_material_id,
@@ -182,7 +273,7 @@ impl MaterialData {
                         Color::WHITE,
                         false,
                     )
-                    .map_err(|_| LevelMaterialLoadError::MaterialIndex)?;
+                    .map_err(LevelMaterialLoadError::MaterialIndex)?;
             }
             None => {
                 material_index_data
@@ -193,10 +284,11 @@ impl MaterialData {
                         Color::WHITE,
                         false,
                     )
-                    .map_err(|_| LevelMaterialLoadError::MaterialIndex)?;
+                    .map_err(LevelMaterialLoadError::MaterialIndex)?;
             }
         }
 
+        let texture_bytes = diffuse.iter().map(TextureArray::size_bytes).sum();
         let texture_bind_group = PipelineLevelBindGroupTexture::new(device, &diffuse);
         let material_index = MaterialIndexStorageBuffer::new(device);
         material_index.write(queue, &material_index_data);
@@ -205,6 +297,7 @@ impl MaterialData {
             texture_bind_group,
             material_index,
             lightmap_material_id,
+            texture_bytes,
         });
     }
 }
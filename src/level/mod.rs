@@ -1,12 +1,23 @@
 pub mod cache;
-mod fetch;
+mod cancel;
+mod environment;
+pub mod fetch;
 mod level;
-mod manifest;
+pub mod manifest;
 mod material;
+mod policy;
 pub mod portal;
+mod progress;
 mod render;
+pub mod sky;
 mod state;
 mod trimesh;
+mod watch;
 
-pub use level::{Level, LevelHit, SurfaceKind};
+pub use cancel::LevelLoadCancel;
+pub use fetch::FetchLimits;
+pub use level::{Level, LevelHit, LevelLoadParams, SurfaceKind};
+pub use manifest::{LevelManifestPhysics, LevelManifestSpawn};
+pub use policy::LevelPolicy;
+pub use progress::{LevelLoadProgress, LevelLoadProgressSnapshot};
 pub use render::{LevelRenderParams, LevelRenderSchema, LevelRenderState};
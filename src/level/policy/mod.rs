@@ -0,0 +1,6 @@
+mod policy;
+
+#[cfg(test)]
+mod test;
+
+pub use policy::{LevelPolicy, LevelPolicyError};
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+const REMOTE_SCHEMES: [&str; 2] = ["http", "https"];
+
+#[derive(Debug, Clone)]
+pub enum LevelPolicyError {
+    LocalFromRemote(String),
+    CrossOrigin(String),
+    Denied(String),
+}
+
+impl std::fmt::Display for LevelPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelPolicyError::LocalFromRemote(url) => {
+                write!(f, "remote level cannot reach local URL {url}")
+            }
+            LevelPolicyError::CrossOrigin(url) => {
+                write!(f, "asset {url} is not on the level's origin")
+            }
+            LevelPolicyError::Denied(url) => write!(f, "{url} is blocked by the domain list"),
+        };
+    }
+}
+
+impl std::error::Error for LevelPolicyError {}
+
+// Which URLs a level may load from or link to. Remote levels can never reach `file://`
+// URLs; the rest is user configurable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LevelPolicy {
+    #[serde(default)]
+    pub same_origin_assets: bool,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+fn is_remote(url: &Url) -> bool {
+    return REMOTE_SCHEMES.contains(&url.scheme());
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    return a.scheme() == b.scheme()
+        && a.host() == b.host()
+        && a.port_or_known_default() == b.port_or_known_default();
+}
+
+// `domain` covers itself and its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let domain = domain.to_ascii_lowercase();
+    return host == domain || host.ends_with(&format!(".{domain}"));
+}
+
+impl LevelPolicy {
+    fn domain_check(&self, url: &Url) -> Result<(), LevelPolicyError> {
+        let Some(host) = url.host_str() else {
+            return Ok(());
+        };
+        let denied = self.deny.iter().any(|domain| domain_matches(host, domain));
+        let allowed =
+            self.allow.is_empty() || self.allow.iter().any(|domain| domain_matches(host, domain));
+        if denied || !allowed {
+            return Err(LevelPolicyError::Denied(url.to_string()));
+        }
+        return Ok(());
+    }
+
+    fn escalation_check(&self, from: &Url, to: &Url) -> Result<(), LevelPolicyError> {
+        if is_remote(from) && !is_remote(to) {
+            return Err(LevelPolicyError::LocalFromRemote(to.to_string()));
+        }
+        return Ok(());
+    }
+
+    pub fn level_check(&self, url: &Url) -> Result<(), LevelPolicyError> {
+        return self.domain_check(url);
+    }
+
+    pub fn asset_check(&self, level_url: &Url, url: &Url) -> Result<(), LevelPolicyError> {
+        self.escalation_check(level_url, url)?;
+        if self.same_origin_assets && !same_origin(level_url, url) {
+            return Err(LevelPolicyError::CrossOrigin(url.to_string()));
+        }
+        return self.domain_check(url);
+    }
+
+    // Links to blocked domains are left for the destination's own `level_check`, so a
+    // level is only rejected for links that could never be followed safely.
+    pub fn link_check(&self, level_url: &Url, url: &Url) -> Result<(), LevelPolicyError> {
+        return self.escalation_check(level_url, url);
+    }
+}
@@ -0,0 +1,77 @@
+use url::Url;
+
+use super::*;
+
+fn url(s: &str) -> Url {
+    return Url::parse(s).unwrap();
+}
+
+#[test]
+fn test_remote_level_cannot_reach_local_files() {
+    let policy = LevelPolicy::default();
+    let level = url("https://example.com/hall/manifest.json");
+    let local = url("file:///etc/passwd");
+
+    assert!(matches!(
+        policy.asset_check(&level, &local),
+        Err(LevelPolicyError::LocalFromRemote(_))
+    ));
+    assert!(matches!(
+        policy.link_check(&level, &local),
+        Err(LevelPolicyError::LocalFromRemote(_))
+    ));
+}
+
+#[test]
+fn test_local_level_can_reach_local_and_remote() {
+    let policy = LevelPolicy::default();
+    let level = url("file:///home/me/hall/manifest.json");
+
+    assert!(policy
+        .asset_check(&level, &url("file:///home/me/hall/mesh.glb"))
+        .is_ok());
+    assert!(policy
+        .link_check(&level, &url("https://example.com/manifest.json"))
+        .is_ok());
+}
+
+#[test]
+fn test_same_origin_assets() {
+    let policy = LevelPolicy {
+        same_origin_assets: true,
+        ..LevelPolicy::default()
+    };
+    let level = url("https://example.com/hall/manifest.json");
+
+    assert!(policy
+        .asset_check(&level, &url("https://example.com:443/shared/wall.png"))
+        .is_ok());
+    assert!(matches!(
+        policy.asset_check(&level, &url("https://cdn.example.net/wall.png")),
+        Err(LevelPolicyError::CrossOrigin(_))
+    ));
+}
+
+#[test]
+fn test_domain_lists() {
+    let policy = LevelPolicy {
+        allow: vec!["example.com".to_string()],
+        deny: vec!["bad.example.com".to_string()],
+        ..LevelPolicy::default()
+    };
+
+    assert!(policy
+        .level_check(&url("https://halls.example.com/manifest.json"))
+        .is_ok());
+    assert!(matches!(
+        policy.level_check(&url("https://bad.example.com/manifest.json")),
+        Err(LevelPolicyError::Denied(_))
+    ));
+    assert!(matches!(
+        policy.level_check(&url("https://example.org/manifest.json")),
+        Err(LevelPolicyError::Denied(_))
+    ));
+    assert!(policy
+        .level_check(&url("file:///home/me/manifest.json"))
+        .is_ok());
+}
@@ -17,6 +17,42 @@ pub enum LevelPortalGeometryFromGLTFError {
     UnstableAnchor,
 }
 
+impl std::fmt::Display for LevelPortalGeometryFromGLTFError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelPortalGeometryFromGLTFError::InsufficientVertices => {
+                write!(f, "portal mesh has fewer than 3 distinct vertices")
+            }
+            LevelPortalGeometryFromGLTFError::DegenerateGeometry => {
+                write!(f, "portal mesh has no usable surface normal")
+            }
+            LevelPortalGeometryFromGLTFError::NotCoplanar => {
+                write!(f, "portal mesh vertices are not coplanar")
+            }
+            LevelPortalGeometryFromGLTFError::TiltedPortal => {
+                write!(f, "portal must be a wall, floor or ceiling")
+            }
+            LevelPortalGeometryFromGLTFError::InconsistentColors => {
+                write!(
+                    f,
+                    "portal mesh has overlapping vertices with different colors"
+                )
+            }
+            LevelPortalGeometryFromGLTFError::MissingAnchorColor => {
+                write!(f, "portal mesh has no magenta anchor vertex")
+            }
+            LevelPortalGeometryFromGLTFError::AmbiguousAnchorColor => {
+                write!(f, "portal mesh has more than one magenta anchor vertex")
+            }
+            LevelPortalGeometryFromGLTFError::UnstableAnchor => {
+                write!(f, "portal anchor vertex is at the portal center")
+            }
+        };
+    }
+}
+
+impl std::error::Error for LevelPortalGeometryFromGLTFError {}
+
 #[derive(Debug, Clone)]
 pub struct LevelPortalGeometry {
     center: Vec3,
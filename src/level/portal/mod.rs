@@ -9,4 +9,4 @@ mod test;
 pub use geometry::LevelPortalGeometry;
 pub use kind::LevelPortalKind;
 pub use link::LevelPortalLink;
-pub use portal::LevelPortal;
+pub use portal::{LevelPortal, LevelPortalLoadError};
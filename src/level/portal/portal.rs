@@ -1,24 +1,53 @@
+use std::borrow::Cow;
+
 use parry3d::math::{Isometry, Vector};
 use parry3d::query::{cast_shapes, ShapeCastHit, ShapeCastOptions};
 use parry3d::shape::{Cuboid, TriMesh};
 use url::Url;
 
-use crate::gltf::GLTFMesh;
-use crate::graphics::model::Model;
+use crate::gltf::{GLTFMesh, GLTFMeshError};
+use crate::graphics::model::{Model, ModelUploadError};
 use crate::level::cache::{LevelCache, LevelCacheResult};
-use crate::level::fetch::fetch;
+use crate::level::fetch::{FetchBatch, FetchError};
+use crate::level::LevelManifestSpawn;
 
-use super::super::trimesh::trimesh_from_vertices;
-use super::geometry::LevelPortalGeometry;
+use super::super::trimesh::{trimesh_from_vertices, trimesh_size_bytes};
+use super::geometry::{LevelPortalGeometry, LevelPortalGeometryFromGLTFError};
 use super::LevelPortalLink;
 
 #[derive(Debug)]
 pub enum LevelPortalLoadError {
-    URLJoin,
-    Fetch,
-    GLTF,
-    GeometryFromGLTF,
-    ModelUpload,
+    URLJoin(String),
+    Fetch(String, FetchError),
+    GLTF(String, GLTFMeshError),
+    GeometryFromGLTF(String, LevelPortalGeometryFromGLTFError),
+    ModelUpload(ModelUploadError),
+}
+
+impl std::fmt::Display for LevelPortalLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelPortalLoadError::URLJoin(href) => write!(f, "invalid URL \"{href}\""),
+            LevelPortalLoadError::Fetch(url, _) => write!(f, "failed to fetch {url}"),
+            LevelPortalLoadError::GLTF(url, _) => write!(f, "failed to parse mesh {url}"),
+            LevelPortalLoadError::GeometryFromGLTF(url, _) => {
+                write!(f, "invalid portal geometry in {url}")
+            }
+            LevelPortalLoadError::ModelUpload(_) => write!(f, "failed to upload portal model"),
+        };
+    }
+}
+
+impl std::error::Error for LevelPortalLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            LevelPortalLoadError::URLJoin(_) => None,
+            LevelPortalLoadError::Fetch(_, err) => Some(err),
+            LevelPortalLoadError::GLTF(_, err) => Some(err),
+            LevelPortalLoadError::GeometryFromGLTF(_, err) => Some(err),
+            LevelPortalLoadError::ModelUpload(err) => Some(err),
+        };
+    }
 }
 
 pub struct LevelPortal {
@@ -40,6 +69,8 @@ impl LevelPortal {
 
     pub fn load(
         base_url: &Url,
+        link_base_url: &Url,
+        batch: &FetchBatch,
         mesh_href: &str,
         link_href: &str,
         device: &wgpu::Device,
@@ -47,16 +78,18 @@ impl LevelPortal {
     ) -> Result<Self, LevelPortalLoadError> {
         let mesh_url = base_url
             .join(mesh_href)
-            .map_err(|_| LevelPortalLoadError::URLJoin)?;
-        let mesh_data = fetch(&mesh_url).map_err(|_| LevelPortalLoadError::Fetch)?;
-        let portal_mesh =
-            GLTFMesh::from_bytes(&mesh_data).map_err(|_| LevelPortalLoadError::GLTF)?;
-
-        let link = base_url
+            .map_err(|_| LevelPortalLoadError::URLJoin(mesh_href.to_string()))?;
+        let mesh_data = batch
+            .get(&mesh_url)
+            .map_err(|err| LevelPortalLoadError::Fetch(mesh_url.to_string(), err))?;
+        let portal_mesh = GLTFMesh::from_bytes(mesh_data)
+            .map_err(|err| LevelPortalLoadError::GLTF(mesh_url.to_string(), err))?;
+
+        let link = link_base_url
             .join(link_href)
-            .map_err(|_| LevelPortalLoadError::URLJoin)?;
+            .map_err(|_| LevelPortalLoadError::URLJoin(link_href.to_string()))?;
         let geometry = LevelPortalGeometry::from_gltf(portal_mesh.vertices())
-            .map_err(|_| LevelPortalLoadError::GeometryFromGLTF)?;
+            .map_err(|err| LevelPortalLoadError::GeometryFromGLTF(mesh_url.to_string(), err))?;
 
         let portal_vertices: Vec<_> = portal_mesh.vertices().collect();
         let portal_buffer: Vec<_> = portal_vertices
@@ -66,7 +99,7 @@ impl LevelPortal {
         let mut portal_model = Model::new(device, portal_mesh.vertex_count());
         portal_model
             .upload(queue, &portal_buffer)
-            .map_err(|_| LevelPortalLoadError::ModelUpload)?;
+            .map_err(LevelPortalLoadError::ModelUpload)?;
         let portal_collider = trimesh_from_vertices(portal_vertices.into_iter());
 
         return Ok(Self::new(geometry, portal_model, portal_collider, link));
@@ -80,6 +113,10 @@ impl LevelPortal {
         return &self.link;
     }
 
+    pub fn size_bytes(&self) -> u64 {
+        return self.model.size_bytes() + trimesh_size_bytes(&self.collider);
+    }
+
     pub fn draw<'a>(&'a self, rp: &mut wgpu::RenderPass<'a>) {
         self.model.draw(rp);
     }
@@ -103,6 +140,14 @@ impl LevelPortal {
         .unwrap();
     }
 
+    // A `#spawn=<name>` link drops the player at a spawn instead of joining a portal.
+    pub fn spawn_link(&self) -> Option<(Url, Cow<'_, str>)> {
+        let name = LevelManifestSpawn::fragment_name(&self.link)?;
+        let mut url = self.link.clone();
+        url.set_fragment(None);
+        return Some((url, name));
+    }
+
     pub fn link(&self, cache: &mut LevelCache) -> Option<LevelPortalLink> {
         let fragment = self.link.fragment()?;
         let mut url = self.link.clone();
@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+
+use url::Url;
+
+#[derive(Clone, Debug, Default)]
+pub struct LevelLoadProgressSnapshot {
+    pub assets_done: usize,
+    pub assets_total: usize,
+    pub bytes_received: u64,
+    pub bytes_expected: u64,
+    pub asset: Option<String>,
+}
+
+impl LevelLoadProgressSnapshot {
+    pub fn fraction(&self) -> f32 {
+        if self.assets_total == 0 {
+            return 0.0;
+        }
+        return self.assets_done as f32 / self.assets_total as f32;
+    }
+}
+
+// Shared between a loader thread, which reports as assets arrive, and the cache, which
+// hands out snapshots while the level is loading.
+#[derive(Clone, Default)]
+pub struct LevelLoadProgress {
+    snapshot: Arc<Mutex<LevelLoadProgressSnapshot>>,
+}
+
+impl LevelLoadProgress {
+    pub fn assets_add(&self, count: usize) {
+        self.snapshot.lock().unwrap().assets_total += count;
+    }
+
+    // `expected` is the response's Content-Length, when it sent one.
+    pub fn asset_start(&self, url: &Url, expected: Option<u64>) {
+        let name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default();
+        let mut snapshot = self.snapshot.lock().unwrap();
+        snapshot.asset = Some(name.to_string());
+        snapshot.bytes_expected += expected.unwrap_or(0);
+    }
+
+    pub fn bytes_add(&self, count: u64) {
+        self.snapshot.lock().unwrap().bytes_received += count;
+    }
+
+    pub fn asset_done(&self) {
+        self.snapshot.lock().unwrap().assets_done += 1;
+    }
+
+    pub fn snapshot(&self) -> LevelLoadProgressSnapshot {
+        return self.snapshot.lock().unwrap().clone();
+    }
+}
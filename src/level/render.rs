@@ -7,17 +7,11 @@ use crate::graphics::pipeline::level::PipelineLevelBindGroupConfig;
 use crate::graphics::pipeline::portal::{
     bind_portal_constants, PipelinePortalBindGroupConfig, PipelinePortalBindGroupTexture,
 };
+use crate::graphics::pipeline::sky::bind_sky_constants;
 use crate::graphics::render_target::RenderTarget;
 use crate::graphics::uniform::{UniformCamera, UniformCameraData};
 use crate::level::cache::{LevelCache, LevelCacheResult};
 
-const CLEAR_COLOR: wgpu::Color = wgpu::Color {
-    r: 0.0,
-    g: 0.0,
-    b: 0.0,
-    a: 1.0,
-};
-
 #[derive(Default)]
 pub struct LevelRenderState {
     pub camera: u32,
@@ -36,6 +30,7 @@ pub struct LevelRenderParams<'a> {
     pub state: &'a mut LevelRenderState,
     pub pipeline_level: &'a wgpu::RenderPipeline,
     pub pipeline_portal: &'a wgpu::RenderPipeline,
+    pub pipeline_sky: &'a wgpu::RenderPipeline,
     pub color_view: &'a wgpu::TextureView,
     pub depth_view: &'a wgpu::TextureView,
     pub eye: Vec3,
@@ -58,6 +53,7 @@ pub enum LevelRenderSchema {
 
 pub fn level_render(level_state: &LevelState, params: LevelRenderParams) {
     let material_data = &level_state.material_data;
+    let environment = &level_state.environment;
     let level_bind_group_config = PipelineLevelBindGroupConfig::new(
         params.device,
         params.camera,
@@ -83,7 +79,7 @@ pub fn level_render(level_state: &LevelState, params: LevelRenderParams) {
                     view: params.color_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(CLEAR_COLOR),
+                        load: wgpu::LoadOp::Clear(environment.clear),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -98,10 +94,27 @@ pub fn level_render(level_state: &LevelState, params: LevelRenderParams) {
                 ..Default::default()
             });
 
+        if let Some(sky) = &environment.sky {
+            // The sky turns with the camera but never moves with it.
+            let mut sky_camera_data = camera_data;
+            sky_camera_data.view_set(Vec3::ZERO, params.player_rotation);
+            let inverse_view_projection = (params.projection * sky_camera_data.view).inverse();
+
+            rp.set_pipeline(params.pipeline_sky);
+            sky.bind(&mut rp);
+            bind_sky_constants(&mut rp, inverse_view_projection);
+            rp.draw(0..3, 0..1);
+        }
+
         rp.set_pipeline(params.pipeline_level);
         material_data.texture_bind_group.bind(&mut rp);
         level_bind_group_config.bind(&mut rp, camera_offset);
-        bind_level_constants(&mut rp, params.tick, material_data.lightmap_material_id);
+        bind_level_constants(
+            &mut rp,
+            params.tick,
+            material_data.lightmap_material_id,
+            environment.fog,
+        );
         level_state.model.draw(&mut rp);
     }
 
@@ -178,6 +191,7 @@ pub fn level_render(level_state: &LevelState, params: LevelRenderParams) {
                     state: &mut *params.state,
                     pipeline_level: params.pipeline_level,
                     pipeline_portal: params.pipeline_portal,
+                    pipeline_sky: params.pipeline_sky,
                     color_view: rt.color_view(),
                     depth_view: rt.depth_view(),
                     eye: link.position_transform(params.eye, false),
@@ -0,0 +1,96 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec3;
+use image::RgbaImage;
+
+const FACE_SIZE_MIN: u32 = 4;
+const FACE_SIZE_MAX: u32 = 2048;
+
+#[derive(Debug)]
+pub enum LevelSkyError {
+    EquirectangularSize(u32, u32),
+    FaceSize(u32, u32),
+    FaceMismatch,
+}
+
+impl std::fmt::Display for LevelSkyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            LevelSkyError::EquirectangularSize(w, h) => write!(
+                f,
+                "equirectangular sky is {w}x{h}, expected twice as wide as tall with a width \
+                 that is a multiple of 4, from {}x{} up to {}x{}",
+                FACE_SIZE_MIN * 4,
+                FACE_SIZE_MIN * 2,
+                FACE_SIZE_MAX * 4,
+                FACE_SIZE_MAX * 2
+            ),
+            LevelSkyError::FaceSize(w, h) => write!(
+                f,
+                "sky face is {w}x{h}, expected a square from {FACE_SIZE_MIN} to \
+                 {FACE_SIZE_MAX} pixels wide"
+            ),
+            LevelSkyError::FaceMismatch => write!(f, "sky faces are not all the same size"),
+        };
+    }
+}
+
+impl std::error::Error for LevelSkyError {}
+
+// The world direction through the centre of face texel (x, y), for faces ordered +X, -X,
+// +Y, -Y, +Z, -Z as the GPU samples them.
+fn face_direction(face: usize, x: u32, y: u32, size: u32) -> Vec3 {
+    let s = 2.0 * (x as f32 + 0.5) / size as f32 - 1.0;
+    let t = 2.0 * (y as f32 + 0.5) / size as f32 - 1.0;
+    let direction = match face {
+        0 => Vec3::new(1.0, -t, -s),
+        1 => Vec3::new(-1.0, -t, s),
+        2 => Vec3::new(s, 1.0, t),
+        3 => Vec3::new(s, -1.0, -t),
+        4 => Vec3::new(s, -t, 1.0),
+        _ => Vec3::new(-s, -t, -1.0),
+    };
+    return direction.normalize();
+}
+
+// Checks cubemap faces, returning their shared size.
+pub fn sky_cubemap_size(faces: &[RgbaImage; 6]) -> Result<u32, LevelSkyError> {
+    let (w, h) = faces[0].dimensions();
+    if w != h || !(FACE_SIZE_MIN..=FACE_SIZE_MAX).contains(&w) {
+        return Err(LevelSkyError::FaceSize(w, h));
+    }
+    if faces.iter().any(|face| face.dimensions() != (w, h)) {
+        return Err(LevelSkyError::FaceMismatch);
+    }
+    return Ok(w);
+}
+
+// Checks an equirectangular image, returning the size of the cubemap faces it becomes.
+pub fn sky_equirectangular_size(image: &RgbaImage) -> Result<u32, LevelSkyError> {
+    let (w, h) = image.dimensions();
+    let size = w / 4;
+    if w != h * 2 || w % 4 != 0 || !(FACE_SIZE_MIN..=FACE_SIZE_MAX).contains(&size) {
+        return Err(LevelSkyError::EquirectangularSize(w, h));
+    }
+    return Ok(size);
+}
+
+// Resamples an equirectangular image onto cubemap faces. The image centre faces -Z, and
+// its left and right edges meet behind, at +Z.
+pub fn sky_equirectangular_faces(image: &RgbaImage) -> Result<[RgbaImage; 6], LevelSkyError> {
+    let size = sky_equirectangular_size(image)?;
+    let (w, h) = image.dimensions();
+
+    return Ok(std::array::from_fn(|face| {
+        return RgbaImage::from_fn(size, size, |x, y| {
+            let direction = face_direction(face, x, y, size);
+            let longitude = direction.x.atan2(-direction.z);
+            let latitude = direction.y.clamp(-1.0, 1.0).asin();
+            let u = 0.5 + longitude / TAU;
+            let v = 0.5 - latitude / PI;
+            let px = ((u * w as f32) as u32).min(w - 1);
+            let py = ((v * h as f32) as u32).min(h - 1);
+            return *image.get_pixel(px, py);
+        });
+    }));
+}
@@ -1,4 +1,4 @@
-use glam::Vec3;
+use image::RgbaImage;
 use std::collections::HashMap;
 use url::Url;
 
@@ -7,7 +7,8 @@ use parry3d::shape::TriMesh;
 use crate::audio::TrackData;
 use crate::graphics::model::Model;
 
-use super::manifest::LevelManifestMeta;
+use super::environment::LevelEnvironment;
+use super::manifest::{LevelManifestMeta, LevelManifestPhysics, LevelManifestSpawn};
 use super::material::MaterialData;
 use super::portal::LevelPortal;
 
@@ -18,11 +19,18 @@ pub struct LevelColliderData {
 
 pub struct LevelState {
     pub url: Url,
+    pub source_urls: Vec<Url>,
     pub meta: LevelManifestMeta,
-    pub spawn: Vec3,
+    pub spawn: LevelManifestSpawn,
+    pub spawns: HashMap<String, LevelManifestSpawn>,
+    pub physics: LevelManifestPhysics,
+    pub unknown_fields: Vec<String>,
+    pub warnings: Vec<String>,
+    pub environment: LevelEnvironment,
     pub collider_data: LevelColliderData,
     pub model: Model,
     pub material_data: MaterialData,
     pub portals: HashMap<String, LevelPortal>,
     pub track: Option<TrackData>,
+    pub thumbnail: Option<RgbaImage>,
 }
@@ -19,3 +19,9 @@ pub fn trimesh_from_vertices(gltf_vertices: impl Iterator<Item = GLTFVertex>) ->
 
     return TriMesh::new(vertices, indices);
 }
+
+pub fn trimesh_size_bytes(mesh: &TriMesh) -> u64 {
+    let vertices = std::mem::size_of_val(mesh.vertices());
+    let indices = std::mem::size_of_val(mesh.indices());
+    return (vertices + indices) as u64;
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use url::Url;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+struct LevelWatchFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl LevelWatchFile {
+    fn modified(path: &Path) -> Option<SystemTime> {
+        return fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    }
+}
+
+// Modification times taken before a load starts. Tracking a level against them rather than
+// against the times once it finishes means edits made during the load still count as changes.
+pub struct LevelWatchSnapshot {
+    modified: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+fn file_path(url: &Url) -> Option<PathBuf> {
+    if url.scheme() != "file" {
+        return None;
+    }
+    return url.to_file_path().ok();
+}
+
+// Polls the modification times of the local files behind `file://` levels.
+pub struct LevelWatch {
+    levels: HashMap<Url, Vec<LevelWatchFile>>,
+    polled: Instant,
+}
+
+impl LevelWatch {
+    pub fn new() -> Self {
+        return Self {
+            levels: HashMap::new(),
+            polled: Instant::now(),
+        };
+    }
+
+    // Covers the files `url` is known to read: itself, and the sources of its last load.
+    pub fn snapshot(&self, url: &Url) -> LevelWatchSnapshot {
+        let tracked = self
+            .levels
+            .get(url)
+            .into_iter()
+            .flatten()
+            .map(|file| file.path.clone());
+        let modified = file_path(url)
+            .into_iter()
+            .chain(tracked)
+            .map(|path| {
+                let modified = LevelWatchFile::modified(&path);
+                return (path, modified);
+            })
+            .collect();
+        return LevelWatchSnapshot { modified };
+    }
+
+    // Files missing from `snapshot`, first read by this load, are taken as they are now.
+    pub fn track(&mut self, url: &Url, source_urls: &[Url], snapshot: &LevelWatchSnapshot) {
+        let files: Vec<LevelWatchFile> = source_urls
+            .iter()
+            .filter_map(file_path)
+            .map(|path| {
+                let modified = match snapshot.modified.get(&path) {
+                    Some(modified) => *modified,
+                    None => LevelWatchFile::modified(&path),
+                };
+                return LevelWatchFile { path, modified };
+            })
+            .collect();
+
+        if files.is_empty() {
+            self.levels.remove(url);
+            return;
+        }
+        self.levels.insert(url.clone(), files);
+    }
+
+    pub fn untrack(&mut self, url: &Url) {
+        self.levels.remove(url);
+    }
+
+    // Levels with a file modified, created or removed since it was last seen. Their
+    // snapshots are refreshed, so each change is reported once.
+    pub fn changed(&mut self) -> Vec<Url> {
+        if self.polled.elapsed() < POLL_INTERVAL {
+            return Vec::new();
+        }
+        self.polled = Instant::now();
+
+        let mut changed = Vec::new();
+        for (url, files) in self.levels.iter_mut() {
+            let mut dirty = false;
+            for file in files.iter_mut() {
+                let modified = LevelWatchFile::modified(&file.path);
+                if modified != file.modified {
+                    file.modified = modified;
+                    dirty = true;
+                }
+            }
+            if dirty {
+                changed.push(url.clone());
+            }
+        }
+        return changed;
+    }
+}
@@ -0,0 +1,61 @@
+// The game's modules, shared by the `halls` binary and the `mirror` and `halls-lint` tools.
+
+pub mod audio;
+pub mod color;
+pub mod config;
+pub mod gltf;
+pub mod graphics;
+pub mod level;
+pub mod overlay;
+pub mod player;
+pub mod window;
+
+#[cfg(test)]
+#[path = "../test/stand_in.rs"]
+mod stand_in;
+
+use std::time::Duration;
+
+use include_dir::include_dir;
+
+pub static ASSET: include_dir::Dir = include_dir!("$CARGO_MANIFEST_DIR/asset");
+pub const WINDOW_TITLE: &str = "Halls";
+
+pub const SIM_STEP: Duration = Duration::from_millis(10);
+
+#[derive(PartialEq)]
+pub enum Status {
+    Intro,
+    MenuHome,
+    MenuVisit,
+    MenuSettings,
+    Simulation,
+}
+
+pub struct StatusBuffer {
+    current: Status,
+    next: Option<Status>,
+}
+
+impl StatusBuffer {
+    pub fn new(status: Status) -> Self {
+        return Self {
+            current: status,
+            next: None,
+        };
+    }
+
+    pub fn get(&self) -> &Status {
+        return &self.current;
+    }
+
+    pub fn set(&mut self, status: Status) {
+        self.next = Some(status);
+    }
+
+    pub fn swap(&mut self) {
+        if let Some(next) = self.next.take() {
+            self.current = next;
+        }
+    }
+}
@@ -1,46 +1,34 @@
 // Avoid spawning a console window on Windows builds.
 #![windows_subsystem = "windows"]
 
-mod audio;
-mod color;
-mod config;
-mod gltf;
-mod graphics;
-mod level;
-mod overlay;
-mod player;
-mod window;
-
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use include_dir::include_dir;
 use rodio::{OutputStream, Sink};
 
-pub static ASSET: include_dir::Dir = include_dir!("$CARGO_MANIFEST_DIR/asset");
-pub const WINDOW_TITLE: &str = "Halls";
-
 use glam::{Mat4, Vec2, Vec4};
-use graphics::pipeline::level::pipeline_level_create;
-use graphics::pipeline::overlay::bind_group::{
+use halls::graphics::pipeline::level::pipeline_level_create;
+use halls::graphics::pipeline::overlay::bind_group::{
     PipelineOverlayBindGroupConfig, PipelineOverlayBindGroupTexture,
 };
-use graphics::pipeline::overlay::pipeline_overlay_create;
-use graphics::pipeline::portal::pipeline_portal_create;
-use graphics::uniform::UniformCamera;
-
-use config::Config;
-use graphics::model::{Model, ModelVertex};
-use graphics::render_target::RenderTarget;
-use level::cache::{LevelCache, LevelCacheResult};
-use level::{LevelRenderParams, LevelRenderSchema, LevelRenderState};
-use overlay::MenuHomeUpdateParams;
-use player::Player;
-use window::WindowKeyState;
-use window::{Window, WindowContext, WindowHandler, WindowHandlerEvent};
+use halls::graphics::pipeline::overlay::pipeline_overlay_create;
+use halls::graphics::pipeline::portal::pipeline_portal_create;
+use halls::graphics::pipeline::sky::pipeline_sky_create;
+use halls::graphics::uniform::UniformCamera;
+use halls::{audio, graphics, overlay, Status, StatusBuffer, ASSET, SIM_STEP};
+
+use halls::config::{Config, ConfigControl};
+use halls::graphics::model::{Model, ModelVertex};
+use halls::graphics::render_target::RenderTarget;
+use halls::level::cache::{LevelCache, LevelCacheResult, LevelLoader};
+use halls::level::fetch::FetchDiskCache;
+use halls::level::{LevelRenderParams, LevelRenderSchema, LevelRenderState};
+use halls::overlay::MenuHomeUpdateParams;
+use halls::player::Player;
+use halls::window::WindowKeyState;
+use halls::window::{Window, WindowContext, WindowHandler, WindowHandlerEvent};
 use winit::keyboard::{Key, NamedKey};
 
-pub const SIM_STEP: Duration = Duration::from_millis(10);
 pub const TARGET_WIDTH: f32 = 1280.0;
 
 const JINGLE_AUDIO_PATH: &str = "audio/jingle.wav";
@@ -48,48 +36,12 @@ const SELECT_AUDIO_PATH: &str = "audio/select.wav";
 const MOVE_AUDIO_PATH: &str = "audio/move.wav";
 const WALK_AUDIO_PATH: &str = "audio/walk.wav";
 
-#[derive(PartialEq)]
-pub(crate) enum Status {
-    Intro,
-    MenuHome,
-    MenuVisit,
-    MenuSettings,
-    Simulation,
-}
-
-pub(crate) struct StatusBuffer {
-    current: Status,
-    next: Option<Status>,
-}
-
-impl StatusBuffer {
-    fn new(status: Status) -> Self {
-        return Self {
-            current: status,
-            next: None,
-        };
-    }
-
-    pub fn get(&self) -> &Status {
-        return &self.current;
-    }
-
-    pub fn set(&mut self, status: Status) {
-        self.next = Some(status);
-    }
-
-    fn swap(&mut self) {
-        if let Some(next) = self.next.take() {
-            self.current = next;
-        }
-    }
-}
-
 struct State {
     config: Config,
     status: StatusBuffer,
     pipeline_level: wgpu::RenderPipeline,
     pipeline_portal: wgpu::RenderPipeline,
+    pipeline_sky: wgpu::RenderPipeline,
     pipeline_overlay: wgpu::RenderPipeline,
     depth_texture: graphics::texture::TextureDepth,
     overlay_bind_group_texture: PipelineOverlayBindGroupTexture,
@@ -100,6 +52,7 @@ struct State {
     menu: overlay::MenuHome,
     menu_settings: overlay::MenuSettings,
     menu_visit: overlay::MenuVisit,
+    level_info: overlay::LevelInfo,
     camera: UniformCamera,
     tick: u32,
     projection: Mat4,
@@ -138,6 +91,11 @@ fn update(state: &mut State, ctx: &mut WindowContext<'_>) {
     state.master_sink.set_volume(state.config.volume);
     state.status.swap();
     state.cache.update();
+    if let Some(level_url) = state.player.level_url() {
+        state
+            .cache
+            .neighbourhood_update(level_url, state.config.prefetch_depth);
+    }
     state.cross_fader.update(&state.player, &mut state.cache);
 
     let size = ctx.size();
@@ -183,6 +141,18 @@ fn update(state: &mut State, ctx: &mut WindowContext<'_>) {
             select_track: &state.select_track,
             move_track: &state.move_track,
         });
+    if matches!(state.status.get(), Status::MenuHome) {
+        state
+            .level_info
+            .update(&mut overlay::LevelInfoUpdateParams {
+                buffer: &mut state.overlay_buffer,
+                resolution,
+                queue: ctx.queue(),
+                texture: &state.overlay_bind_group_texture,
+                player: &state.player,
+                cache: &mut state.cache,
+            });
+    }
     match state.status.get() {
         Status::MenuHome | Status::MenuVisit | Status::MenuSettings => {
             overlay::update_banner(
@@ -192,6 +162,29 @@ fn update(state: &mut State, ctx: &mut WindowContext<'_>) {
                 &mut state.cache,
             );
         }
+        Status::Simulation => {
+            overlay::update_portal_progress(
+                &mut state.overlay_buffer,
+                resolution,
+                &state.player,
+                &mut state.cache,
+                state.projection,
+            );
+            overlay::update_reload_error(
+                &mut state.overlay_buffer,
+                resolution,
+                &state.player,
+                &state.cache,
+            );
+            if state.config.dev_overlay {
+                overlay::update_level_warnings(
+                    &mut state.overlay_buffer,
+                    resolution,
+                    &state.player,
+                    &mut state.cache,
+                );
+            }
+        }
         _ => {}
     }
 
@@ -206,6 +199,12 @@ fn update(state: &mut State, ctx: &mut WindowContext<'_>) {
             state.walk_track.pause();
             state.status.set(Status::MenuHome);
         } else {
+            let reload_key = state.config.key_get(ConfigControl::Reload);
+            if let WindowKeyState::Pressed = ctx.key(reload_key) {
+                if let Some(level_url) = state.player.level_url() {
+                    state.cache.reload(level_url);
+                }
+            }
             state.player.update(ctx, &mut state.cache, &state.config);
             if state.player.is_walking() {
                 state.walk_track.play();
@@ -264,6 +263,7 @@ fn render(state: &mut State, ctx: &WindowContext<'_>) {
                 state: &mut render_ctx_state,
                 pipeline_level: &state.pipeline_level,
                 pipeline_portal: &state.pipeline_portal,
+                pipeline_sky: &state.pipeline_sky,
                 color_view: &color_view,
                 depth_view: state.depth_texture.view(),
                 eye,
@@ -318,6 +318,7 @@ impl WindowHandler for Halls {
 
                 let pipeline_level = pipeline_level_create(ctx.device(), ctx.format());
                 let pipeline_portal = pipeline_portal_create(ctx.device(), ctx.format());
+                let pipeline_sky = pipeline_sky_create(ctx.device(), ctx.format());
                 let pipeline_overlay = pipeline_overlay_create(ctx.device(), ctx.format());
 
                 let depth_texture = graphics::texture::TextureDepth::new(
@@ -336,6 +337,7 @@ impl WindowHandler for Halls {
                 let menu = overlay::MenuHome::new();
                 let menu_settings = overlay::MenuSettings::new(&config);
                 let menu_visit = overlay::MenuVisit::new(&config);
+                let level_info = overlay::LevelInfo::new();
 
                 let camera = UniformCamera::new(ctx.device(), 64);
                 let projection =
@@ -343,8 +345,15 @@ impl WindowHandler for Halls {
 
                 let render_targets = create_render_targets(ctx.device(), size, ctx.format(), 6);
 
-                let mut cache =
-                    LevelCache::new(Arc::clone(ctx.device()), Arc::clone(ctx.queue()), 8);
+                let loader = LevelLoader::new(
+                    Arc::clone(ctx.device()),
+                    Arc::clone(ctx.queue()),
+                    config.fetch_limits,
+                    FetchDiskCache::user(),
+                    config.policy.clone(),
+                    config.strict_manifests,
+                );
+                let mut cache = LevelCache::new(loader, config.cache_budget_bytes, config.watch);
                 cache.get(&config.default_url);
 
                 let (_audio_stream, audio) = OutputStream::try_default().unwrap();
@@ -389,6 +398,7 @@ impl WindowHandler for Halls {
                     status: StatusBuffer::new(Status::Intro),
                     pipeline_level,
                     pipeline_portal,
+                    pipeline_sky,
                     pipeline_overlay,
                     depth_texture,
                     overlay_bind_group_texture,
@@ -399,6 +409,7 @@ impl WindowHandler for Halls {
                     menu,
                     menu_settings,
                     menu_visit,
+                    level_info,
                     camera,
                     tick: 0,
                     projection,
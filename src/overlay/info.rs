@@ -0,0 +1,169 @@
+use std::sync::{Arc, Weak};
+
+use glam::Vec2;
+
+use crate::graphics::model::ModelVertex;
+use crate::graphics::pipeline::overlay::bind_group::PipelineOverlayBindGroupTexture;
+use crate::graphics::sprite::{
+    text_wrap, Sprite, SpriteBorder, SpriteLabel, SpriteLabelAlignment, SpriteMaterial, TextColor,
+    TEXT_SIZE,
+};
+use crate::level::cache::{LevelCache, LevelCacheResult};
+use crate::level::Level;
+use crate::player::Player;
+
+const BORDER: f32 = 3.0;
+const TEXT_PADDING: f32 = 3.0;
+const SCREEN_PADDING: f32 = 6.0;
+const MAX_CHARS: usize = 42;
+const MAX_DESCRIPTION_LINES: usize = 8;
+const INSET: f32 = BORDER + TEXT_PADDING;
+const BOX_WIDTH: f32 = MAX_CHARS as f32 * TEXT_SIZE.x + INSET * 2.0;
+
+// A gray heading followed by `text` wrapped to the panel, skipped when `text` is empty.
+fn section_push<'a>(
+    lines: &mut Vec<(&'a str, TextColor)>,
+    label: &'static str,
+    text: &'a str,
+    max_lines: usize,
+) {
+    if text.is_empty() {
+        return;
+    }
+    lines.push((label, TextColor::Gray));
+    lines.extend(
+        text_wrap(text, MAX_CHARS)
+            .into_iter()
+            .take(max_lines)
+            .map(|line| (line, TextColor::White)),
+    );
+}
+
+pub struct LevelInfoUpdateParams<'a> {
+    pub buffer: &'a mut Vec<ModelVertex>,
+    pub resolution: Vec2,
+    pub queue: &'a wgpu::Queue,
+    pub texture: &'a PipelineOverlayBindGroupTexture,
+    pub player: &'a Player,
+    pub cache: &'a mut LevelCache,
+}
+
+// Credits, license, tags and description of the current level, shown beside the home
+// menu. The overlay texture has room for one thumbnail, so it is uploaded again whenever
+// the level changes.
+pub struct LevelInfo {
+    thumbnail_level: Weak<Level>,
+}
+
+impl LevelInfo {
+    pub fn new() -> Self {
+        return Self {
+            thumbnail_level: Weak::new(),
+        };
+    }
+
+    pub fn update(&mut self, params: &mut LevelInfoUpdateParams) {
+        let Some(level_url) = params.player.level_url() else {
+            return;
+        };
+        let level = match params.cache.get(level_url) {
+            LevelCacheResult::Ready(level) => level,
+            _ => return,
+        };
+
+        if let Some(thumbnail) = level.thumbnail() {
+            if !Weak::ptr_eq(&self.thumbnail_level, &Arc::downgrade(&level)) {
+                params.texture.thumbnail_write(params.queue, thumbnail);
+                self.thumbnail_level = Arc::downgrade(&level);
+            }
+        }
+
+        let meta = level.meta();
+        let credits: Vec<&str> = meta
+            .author()
+            .into_iter()
+            .chain(meta.contributors().iter().map(String::as_str))
+            .collect();
+        let credits = credits.join(", ");
+        let tags = meta.tags().join(", ");
+
+        let mut lines: Vec<(&str, TextColor)> = Vec::new();
+        section_push(&mut lines, "BY", &credits, usize::MAX);
+        section_push(
+            &mut lines,
+            "LICENSE",
+            meta.license().unwrap_or(""),
+            usize::MAX,
+        );
+        section_push(&mut lines, "TAGS", &tags, usize::MAX);
+        section_push(
+            &mut lines,
+            "ABOUT",
+            meta.description().unwrap_or(""),
+            MAX_DESCRIPTION_LINES,
+        );
+
+        let thumbnail_height = level
+            .thumbnail()
+            .map_or(0.0, |thumbnail| thumbnail.height() as f32 + TEXT_PADDING);
+        let box_height = (lines.len() + 1) as f32 * TEXT_SIZE.y + thumbnail_height + INSET * 2.0;
+        let box_pos = Vec2::new(
+            params.resolution.x - BOX_WIDTH - SCREEN_PADDING,
+            SCREEN_PADDING,
+        );
+        params.buffer.extend(
+            SpriteBorder::new(box_pos, Vec2::new(BOX_WIDTH, box_height))
+                .vertices()
+                .map(|vertex| vertex.to_model_vertex(params.resolution)),
+        );
+
+        let content_x = box_pos.x + INSET;
+        let mut y = box_pos.y + INSET;
+        params.buffer.extend(
+            SpriteLabel::new(
+                Vec2::new(content_x, y),
+                MAX_CHARS,
+                TextColor::White,
+                true,
+                SpriteLabelAlignment::Left,
+                meta.name(),
+            )
+            .vertices()
+            .map(|vertex| vertex.to_model_vertex(params.resolution)),
+        );
+        y += TEXT_SIZE.y;
+
+        if let Some(thumbnail) = level.thumbnail() {
+            let (width, height) = thumbnail.dimensions();
+            let size = Vec2::new(width as f32, height as f32);
+            params.buffer.extend(
+                Sprite::new(
+                    Vec2::ZERO,
+                    size,
+                    SpriteMaterial::Thumbnail,
+                    Vec2::new(content_x, y + TEXT_PADDING),
+                    size,
+                )
+                .vertices()
+                .map(|vertex| vertex.to_model_vertex(params.resolution)),
+            );
+            y += thumbnail_height;
+        }
+
+        for (line, color) in lines {
+            params.buffer.extend(
+                SpriteLabel::new(
+                    Vec2::new(content_x, y),
+                    MAX_CHARS,
+                    color,
+                    false,
+                    SpriteLabelAlignment::Left,
+                    line,
+                )
+                .vertices()
+                .map(|vertex| vertex.to_model_vertex(params.resolution)),
+            );
+            y += TEXT_SIZE.y;
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use glam::Vec2;
+
+use crate::graphics::model::ModelVertex;
+use crate::graphics::sprite::{
+    SpriteBorder, SpriteLabel, SpriteLabelAlignment, TextColor, TEXT_SIZE,
+};
+
+const BORDER: f32 = 3.0;
+const TEXT_PADDING: f32 = 3.0;
+const SCREEN_PADDING: f32 = 6.0;
+const MAX_LINES: usize = 6;
+const MAX_CHARS: usize = 60;
+const INSET: f32 = BORDER + TEXT_PADDING;
+const BOX_WIDTH: f32 = MAX_CHARS as f32 * TEXT_SIZE.x + INSET * 2.0;
+
+// Which screen corner a lines box sits in.
+pub enum OverlayLinesCorner {
+    TopLeft,
+    BottomLeft,
+}
+
+// A bordered box with a highlighted `title` above `lines`. Lines past the box's height are
+// summarised as a count, and each is cut to the box's width.
+pub fn overlay_lines_draw(
+    buffer: &mut Vec<ModelVertex>,
+    resolution: Vec2,
+    corner: OverlayLinesCorner,
+    title: &str,
+    lines: &[String],
+) {
+    let mut rows = vec![title.to_string()];
+    if lines.len() < MAX_LINES {
+        rows.extend(lines.iter().cloned());
+    } else {
+        rows.extend(lines.iter().take(MAX_LINES - 2).cloned());
+        rows.push(format!("+{} more", lines.len() - (MAX_LINES - 2)));
+    }
+
+    let box_height = rows.len() as f32 * TEXT_SIZE.y + INSET * 2.0;
+    let box_pos = match corner {
+        OverlayLinesCorner::TopLeft => Vec2::splat(SCREEN_PADDING),
+        OverlayLinesCorner::BottomLeft => {
+            Vec2::new(SCREEN_PADDING, resolution.y - box_height - SCREEN_PADDING)
+        }
+    };
+    buffer.extend(
+        SpriteBorder::new(box_pos, Vec2::new(BOX_WIDTH, box_height))
+            .vertices()
+            .map(|vertex| vertex.to_model_vertex(resolution)),
+    );
+
+    for (i, row) in rows.iter().enumerate() {
+        let mut len = row.len().min(MAX_CHARS);
+        while !row.is_char_boundary(len) {
+            len -= 1;
+        }
+        let position = box_pos + Vec2::new(INSET, INSET + i as f32 * TEXT_SIZE.y);
+        buffer.extend(
+            SpriteLabel::new(
+                position,
+                MAX_CHARS,
+                TextColor::White,
+                i == 0,
+                SpriteLabelAlignment::Left,
+                &row[..len],
+            )
+            .vertices()
+            .map(|vertex| vertex.to_model_vertex(resolution)),
+        );
+    }
+}
@@ -42,6 +42,7 @@ pub enum MenuSettingsItem {
     StrafeRight,
     Jump,
     Crouch,
+    Reload,
     DefaultUrl,
     Save,
     GoBack,
@@ -91,6 +92,7 @@ impl MenuSettingsItem {
             MenuSettingsItem::StrafeRight => Some(ConfigControl::StrafeRight),
             MenuSettingsItem::Jump => Some(ConfigControl::Jump),
             MenuSettingsItem::Crouch => Some(ConfigControl::Crouch),
+            MenuSettingsItem::Reload => Some(ConfigControl::Reload),
             _ => None,
         };
     }
@@ -105,6 +107,7 @@ impl MenuSettingsItem {
             MenuSettingsItem::StrafeRight => "STRAFE RIGHT",
             MenuSettingsItem::Jump => "JUMP",
             MenuSettingsItem::Crouch => "CROUCH",
+            MenuSettingsItem::Reload => "RELOAD",
             MenuSettingsItem::DefaultUrl => "DEFAULT URL",
             MenuSettingsItem::Save => "SAVE",
             MenuSettingsItem::GoBack => "BACK",
@@ -169,7 +172,8 @@ impl MenuSettingsItem {
             | MenuSettingsItem::StrafeLeft
             | MenuSettingsItem::StrafeRight
             | MenuSettingsItem::Jump
-            | MenuSettingsItem::Crouch => {
+            | MenuSettingsItem::Crouch
+            | MenuSettingsItem::Reload => {
                 let control = self.control().unwrap();
                 if let Some(key) = params.window.last_pressed() {
                     params.state.buffered_config.key_set(control, key);
@@ -235,7 +239,8 @@ impl MenuSettingsItem {
             | MenuSettingsItem::StrafeLeft
             | MenuSettingsItem::StrafeRight
             | MenuSettingsItem::Jump
-            | MenuSettingsItem::Crouch => {
+            | MenuSettingsItem::Crouch
+            | MenuSettingsItem::Reload => {
                 let control = self.control().unwrap();
                 Some(key_cache.name(state.buffered_config.key_get(control)))
             }
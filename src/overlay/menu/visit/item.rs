@@ -8,6 +8,7 @@ use crate::graphics::sprite::{
 };
 use crate::level::cache::LevelCache;
 use crate::level::cache::LevelCacheResult;
+use crate::level::{LevelLoadProgressSnapshot, LevelManifestSpawn};
 use crate::player::Player;
 use crate::window::{WindowContext, WindowKeyState};
 use crate::{Status, StatusBuffer};
@@ -19,6 +20,26 @@ const ITEM_INDENT: f32 = TEXT_SIZE.x + 2.0;
 pub const MAX_ITEM_NAME_LEN: usize = 14;
 pub const MAX_ITEM_VALUE_LEN: usize = 48;
 
+const MEBIBYTE: f32 = 1024.0 * 1024.0;
+
+fn progress_message(progress: &LevelLoadProgressSnapshot) -> String {
+    let mut lines = vec![format!(
+        "Loading... {}/{} assets",
+        progress.assets_done, progress.assets_total
+    )];
+    let received = progress.bytes_received as f32 / MEBIBYTE;
+    if progress.bytes_expected > 0 {
+        let expected = progress.bytes_expected as f32 / MEBIBYTE;
+        lines.push(format!("{received:.1}/{expected:.1} MiB"));
+    } else {
+        lines.push(format!("{received:.1} MiB"));
+    }
+    if let Some(ref asset) = progress.asset {
+        lines.push(asset.clone());
+    }
+    return lines.join("\n");
+}
+
 pub struct MenuVisitItemOnSelectParams<'a> {
     pub state: &'a mut MenuVisitState,
     pub status: &'a mut StatusBuffer,
@@ -70,26 +91,49 @@ impl MenuVisitItem {
     pub fn update(&self, params: &mut MenuVisitItemUpdateParams<'_>) {
         if let MenuVisitItem::Visit = self {
             if let Some(ref visiting_url) = params.state.visiting {
+                // A `#spawn=<name>` fragment picks the spawn; the level itself is cached
+                // without it.
+                let mut level_url = visiting_url.clone();
+                level_url.set_fragment(None);
                 if let WindowKeyState::Pressed = params.window.key(&Key::Named(NamedKey::Escape)) {
+                    params.cache.cancel(&level_url);
                     params.state.visiting = None;
                     params.state.selected = false;
                     params.state.status_message = None;
+                    params.state.status_scroll = 0;
+                    params.state.status_progress = None;
                     params.move_track.reset();
                     params.move_track.play();
                     return;
                 }
-                match params.cache.get(visiting_url) {
-                    LevelCacheResult::Loading => {
-                        params.state.status_message = Some("Loading...".to_string());
+                match params.cache.get(&level_url) {
+                    LevelCacheResult::Loading(progress) => {
+                        params.state.status_message = Some(progress_message(&progress));
+                        params.state.status_scroll = 0;
+                        params.state.status_progress = Some(progress.fraction());
                     }
                     LevelCacheResult::Ready(level) => {
-                        params.player.set_position(level.spawn_position());
-                        params.player.set_level_url(visiting_url.clone());
+                        let spawn =
+                            level.spawn(LevelManifestSpawn::fragment_name(visiting_url).as_deref());
+                        params.player.set_position(spawn.position());
+                        params.player.set_rotation(spawn.rotation());
+                        params.player.set_level_url(level_url);
                         params.state.clear();
                         params.status.set(Status::Simulation);
                     }
                     LevelCacheResult::Failed(err) => {
-                        params.state.status_message = Some(err.to_string());
+                        params.state.status_message = Some(err.chain().join("\n"));
+                        params.state.status_progress = None;
+                        if let WindowKeyState::Pressed =
+                            params.window.key(&Key::Named(NamedKey::ArrowUp))
+                        {
+                            params.state.status_scroll =
+                                params.state.status_scroll.saturating_sub(1);
+                        } else if let WindowKeyState::Pressed =
+                            params.window.key(&Key::Named(NamedKey::ArrowDown))
+                        {
+                            params.state.status_scroll += 1;
+                        }
                     }
                 }
             } else {
@@ -8,7 +8,8 @@ use crate::audio::Track;
 use crate::config::Config;
 use crate::graphics::model::ModelVertex;
 use crate::graphics::sprite::{
-    SpriteBorder, SpriteLabel, SpriteLabelAlignment, TextColor, TEXT_SIZE,
+    text_wrap, SpriteBorder, SpriteLabel, SpriteLabelAlignment, SpriteProgress, TextColor,
+    TEXT_SIZE,
 };
 use crate::level::cache::LevelCache;
 use crate::player::Player;
@@ -30,6 +31,8 @@ const ROW_WIDTH: f32 = ITEM_INDENT + (MAX_ITEM_NAME_LEN + MAX_ITEM_VALUE_LEN) as
 const BOX_WIDTH: f32 = ROW_WIDTH + INSET * 2.0;
 const BOX_HEIGHT: f32 = ITEM_COUNT as f32 * TEXT_SIZE.y + INSET * 2.0;
 const STATUS_MAX_CHARS: usize = ((BOX_WIDTH - INSET * 2.0) / TEXT_SIZE.x) as usize;
+const STATUS_MAX_LINES: usize = 6;
+const PROGRESS_HEIGHT: f32 = TEXT_SIZE.y / 2.0;
 
 const WHITE: TextColor = TextColor::White;
 
@@ -40,6 +43,8 @@ pub struct MenuVisitState {
     pub level_url: String,
     pub tick: u32,
     pub status_message: Option<String>,
+    pub status_scroll: usize,
+    pub status_progress: Option<f32>,
 }
 
 impl MenuVisitState {
@@ -49,6 +54,8 @@ impl MenuVisitState {
         self.visiting = None;
         self.tick = 0;
         self.status_message = None;
+        self.status_scroll = 0;
+        self.status_progress = None;
     }
 }
 
@@ -77,6 +84,8 @@ impl MenuVisit {
                 level_url: config.default_url.to_string(),
                 tick: 0,
                 status_message: None,
+                status_scroll: 0,
+                status_progress: None,
             },
         };
     }
@@ -149,8 +158,13 @@ impl MenuVisit {
         }
 
         if let Some(ref message) = self.state.status_message {
+            let lines = text_wrap(message, STATUS_MAX_CHARS);
+            let visible = lines.len().min(STATUS_MAX_LINES);
+            self.state.status_scroll = self.state.status_scroll.min(lines.len() - visible);
+
             let status_y = SCREEN_PADDING + BOX_HEIGHT + SCREEN_PADDING;
-            let status_height = TEXT_SIZE.y + INSET * 2.0;
+            let progress_rows = usize::from(self.state.status_progress.is_some());
+            let status_height = (visible + progress_rows) as f32 * TEXT_SIZE.y + INSET * 2.0;
             params.buffer.extend(
                 SpriteBorder::new(
                     Vec2::new(SCREEN_PADDING, status_y),
@@ -160,19 +174,38 @@ impl MenuVisit {
                 .map(|vertex| vertex.to_model_vertex(params.resolution)),
             );
 
-            let text_pos = Vec2::new(SCREEN_PADDING + INSET, status_y + INSET);
-            params.buffer.extend(
-                SpriteLabel::new(
-                    text_pos,
-                    STATUS_MAX_CHARS,
-                    WHITE,
-                    false,
-                    SpriteLabelAlignment::Left,
-                    message,
-                )
-                .vertices()
-                .map(|vertex| vertex.to_model_vertex(params.resolution)),
-            );
+            let scrolled = lines.iter().skip(self.state.status_scroll).take(visible);
+            for (i, line) in scrolled.enumerate() {
+                let text_pos = Vec2::new(
+                    SCREEN_PADDING + INSET,
+                    status_y + INSET + i as f32 * TEXT_SIZE.y,
+                );
+                params.buffer.extend(
+                    SpriteLabel::new(
+                        text_pos,
+                        STATUS_MAX_CHARS,
+                        WHITE,
+                        false,
+                        SpriteLabelAlignment::Left,
+                        line,
+                    )
+                    .vertices()
+                    .map(|vertex| vertex.to_model_vertex(params.resolution)),
+                );
+            }
+
+            if let Some(fraction) = self.state.status_progress {
+                let progress_pos = Vec2::new(
+                    SCREEN_PADDING + INSET,
+                    status_y + INSET + (visible as f32 + 0.25) * TEXT_SIZE.y,
+                );
+                let progress_size = Vec2::new(BOX_WIDTH - INSET * 2.0, PROGRESS_HEIGHT);
+                params.buffer.extend(
+                    SpriteProgress::new(progress_pos, progress_size, fraction)
+                        .vertices()
+                        .map(|vertex| vertex.to_model_vertex(params.resolution)),
+                );
+            }
         }
     }
 }
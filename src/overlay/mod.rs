@@ -1,9 +1,18 @@
 mod banner;
+mod info;
 mod intro;
+mod lines;
 mod menu;
+mod portal;
+mod reload;
+mod warning;
 
 pub use banner::update_banner;
+pub use info::{LevelInfo, LevelInfoUpdateParams};
 pub use intro::{Intro, IntroUpdateParams};
 pub use menu::{MenuHome, MenuHomeUpdateParams};
 pub use menu::{MenuSettings, MenuSettingsUpdateParams};
 pub use menu::{MenuVisit, MenuVisitUpdateParams};
+pub use portal::update_portal_progress;
+pub use reload::update_reload_error;
+pub use warning::update_level_warnings;
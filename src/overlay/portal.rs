@@ -0,0 +1,57 @@
+use glam::{Mat4, Vec2};
+
+use crate::graphics::model::ModelVertex;
+use crate::graphics::sprite::SpriteProgress;
+use crate::graphics::uniform::UniformCameraData;
+use crate::level::cache::{LevelCache, LevelCacheResult};
+use crate::player::Player;
+
+const PROGRESS_SIZE: Vec2 = Vec2::new(48.0, 4.0);
+
+// Draws a progress bar over each portal of the current level whose destination is still
+// loading for the first time.
+pub fn update_portal_progress(
+    buffer: &mut Vec<ModelVertex>,
+    resolution: Vec2,
+    player: &Player,
+    cache: &mut LevelCache,
+    projection: Mat4,
+) {
+    let Some(level_url) = player.level_url() else {
+        return;
+    };
+    let LevelCacheResult::Ready(level) = cache.get(level_url) else {
+        return;
+    };
+
+    let mut camera_data = UniformCameraData::new();
+    camera_data.view_set(player.eye_position(), player.rotation());
+    let view_projection = projection * camera_data.view;
+
+    for (_, portal) in level.portals() {
+        let mut link_url = portal.link_url().clone();
+        link_url.set_fragment(None);
+        let Some(progress) = cache.progress(&link_url) else {
+            continue;
+        };
+
+        let clip = view_projection * portal.geometry().center().extend(1.0);
+        if clip.w <= 0.0 {
+            continue;
+        }
+        let ndc = clip.truncate() / clip.w;
+        let center = Vec2::new(
+            (ndc.x + 1.0) * 0.5 * resolution.x,
+            (1.0 - ndc.y) * 0.5 * resolution.y,
+        );
+        buffer.extend(
+            SpriteProgress::new(
+                center - PROGRESS_SIZE / 2.0,
+                PROGRESS_SIZE,
+                progress.fraction(),
+            )
+            .vertices()
+            .map(|vertex| vertex.to_model_vertex(resolution)),
+        );
+    }
+}
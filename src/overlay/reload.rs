@@ -0,0 +1,30 @@
+use glam::Vec2;
+
+use super::lines::{overlay_lines_draw, OverlayLinesCorner};
+use crate::graphics::model::ModelVertex;
+use crate::level::cache::LevelCache;
+use crate::player::Player;
+
+const TITLE: &str = "RELOAD FAILED";
+
+pub fn update_reload_error(
+    buffer: &mut Vec<ModelVertex>,
+    resolution: Vec2,
+    player: &Player,
+    cache: &LevelCache,
+) {
+    let Some(level_url) = player.level_url() else {
+        return;
+    };
+    let Some(err) = cache.reload_error(level_url) else {
+        return;
+    };
+
+    overlay_lines_draw(
+        buffer,
+        resolution,
+        OverlayLinesCorner::TopLeft,
+        TITLE,
+        &err.chain(),
+    );
+}
@@ -0,0 +1,47 @@
+use glam::Vec2;
+
+use super::lines::{overlay_lines_draw, OverlayLinesCorner};
+use crate::graphics::model::ModelVertex;
+use crate::level::cache::{LevelCache, LevelCacheResult};
+use crate::player::Player;
+
+const TITLE: &str = "LEVEL WARNINGS";
+
+// Lists what the current level's load worked around, including the manifest keys it
+// ignored, so typos show up while authoring.
+pub fn update_level_warnings(
+    buffer: &mut Vec<ModelVertex>,
+    resolution: Vec2,
+    player: &Player,
+    cache: &mut LevelCache,
+) {
+    let Some(level_url) = player.level_url() else {
+        return;
+    };
+    let level = match cache.get(level_url) {
+        LevelCacheResult::Ready(level) => level,
+        _ => return,
+    };
+    let warnings: Vec<String> = level
+        .warnings()
+        .iter()
+        .cloned()
+        .chain(
+            level
+                .unknown_fields()
+                .iter()
+                .map(|path| format!("unknown field {path}")),
+        )
+        .collect();
+    if warnings.is_empty() {
+        return;
+    }
+
+    overlay_lines_draw(
+        buffer,
+        resolution,
+        OverlayLinesCorner::BottomLeft,
+        TITLE,
+        &warnings,
+    );
+}
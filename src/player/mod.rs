@@ -1,6 +1,7 @@
 mod constant;
 mod crouching;
 mod movement_mode;
+mod physics;
 mod player;
 mod position;
 mod rotation;
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use crate::level::cache::{LevelCache, LevelCacheResult};
+use crate::level::LevelManifestPhysics;
+
+use super::state::PlayerState;
+
+const SPEED: f32 = 8.0;
+const CROUCH_SPEED: f32 = 4.0;
+const GRAVITY: f32 = 20.0;
+const LADDER_CLIMB_SPEED: f32 = 4.0;
+const JUMP_SPEED: f32 = 7.0;
+const STEP_HEIGHT: f32 = 0.5;
+
+// Movement constants for the level the player is in.
+#[derive(Clone, Copy)]
+pub struct PlayerPhysics {
+    pub speed: f32,
+    pub crouch_speed: f32,
+    pub gravity: f32,
+    pub ladder_climb_speed: f32,
+    pub jump_speed: f32,
+    pub step_height: f32,
+}
+
+impl Default for PlayerPhysics {
+    fn default() -> Self {
+        return Self {
+            speed: SPEED,
+            crouch_speed: CROUCH_SPEED,
+            gravity: GRAVITY,
+            ladder_climb_speed: LADDER_CLIMB_SPEED,
+            jump_speed: JUMP_SPEED,
+            step_height: STEP_HEIGHT,
+        };
+    }
+}
+
+impl PlayerPhysics {
+    pub fn from_level(physics: &LevelManifestPhysics) -> Self {
+        return Self {
+            speed: physics.speed().unwrap_or(SPEED),
+            crouch_speed: physics.crouch_speed().unwrap_or(CROUCH_SPEED),
+            gravity: physics.gravity().unwrap_or(GRAVITY),
+            ladder_climb_speed: physics.ladder_climb_speed().unwrap_or(LADDER_CLIMB_SPEED),
+            jump_speed: physics.jump_speed().unwrap_or(JUMP_SPEED),
+            step_height: physics.step_height().unwrap_or(STEP_HEIGHT),
+        };
+    }
+}
+
+// Picks up the level's physics once it is ready, after a visit, a teleport or a reload
+// that replaced the level under the same URL.
+pub fn player_physics_update(state: &mut PlayerState, cache: &mut LevelCache) {
+    let Some(level_url) = &state.level_url else {
+        return;
+    };
+    let LevelCacheResult::Ready(level) = cache.get(level_url) else {
+        return;
+    };
+    if state.physics_level.as_ptr() == Arc::as_ptr(&level) {
+        return;
+    }
+    state.physics = PlayerPhysics::from_level(&level.physics());
+    state.physics_level = Arc::downgrade(&level);
+}
@@ -1,3 +1,5 @@
+use std::sync::Weak;
+
 use glam::{Vec2, Vec3};
 use parry3d::math::Vector;
 use parry3d::shape::Cuboid;
@@ -6,8 +8,9 @@ use url::Url;
 use super::constant::{HEIGHT, WIDTH};
 use super::crouching::player_crouching_update;
 use super::movement_mode::player_movement_mode_update;
+use super::physics::{player_physics_update, PlayerPhysics};
 use super::position::player_position_update;
-use super::rotation::player_rotation_update;
+use super::rotation::{player_pitch_clamp, player_rotation_update};
 use super::state::{PlayerMovementMode, PlayerState};
 use super::teleport::player_try_teleport;
 use super::velocity::player_velocity_update;
@@ -32,6 +35,8 @@ impl Player {
                 velocity: Vec3::ZERO,
                 collider: Cuboid::new(Vector::new(WIDTH / 2.0, HEIGHT / 2.0, WIDTH / 2.0)),
                 level_url: None,
+                physics: PlayerPhysics::default(),
+                physics_level: Weak::new(),
                 last_portal: None,
                 open_factor: 0.0,
                 wish_direction: Vec3::ZERO,
@@ -50,6 +55,7 @@ impl Player {
             return;
         }
 
+        player_physics_update(&mut self.state, cache);
         player_rotation_update(&mut self.state, window, config);
         player_wish_update(&mut self.state, window, config);
         player_crouching_update(&mut self.state, cache);
@@ -72,6 +78,10 @@ impl Player {
         self.state.position = position;
     }
 
+    pub fn set_rotation(&mut self, rotation: Vec2) {
+        self.state.rotation = Vec2::new(player_pitch_clamp(rotation.x), rotation.y);
+    }
+
     pub fn set_level_url(&mut self, url: Url) {
         self.state.level_url = Some(url);
     }
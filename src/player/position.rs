@@ -14,7 +14,6 @@ const MAX_ITERATIONS: usize = 4;
 const STOP_EPSILON: f32 = 0.1;
 const SKIN_THICKNESS: f32 = 0.01;
 const GROUND_SNAP_DISTANCE: f32 = 0.1;
-const STEP_HEIGHT: f32 = 0.5;
 
 fn clip_velocity(velocity: Vec3, planes: &[Vec3]) -> Vec3 {
     // Try clipping against each plane, validating against all others
@@ -120,6 +119,7 @@ pub fn player_position_update(state: &mut PlayerState, cache: &mut LevelCache) {
     let snap_enabled = movement_mode == PlayerMovementMode::Grounded;
 
     let original_vel = state.velocity;
+    let step_height = state.physics.step_height;
 
     // Normal slide move
     slide_move(state, cache);
@@ -131,10 +131,10 @@ pub fn player_position_update(state: &mut PlayerState, cache: &mut LevelCache) {
 
         // Reset to start and sweep up to check ceiling clearance
         state.position = state.prev_position;
-        let up_hit = player_sweep(state, cache, glam::Vec3::Y, STEP_HEIGHT);
+        let up_hit = player_sweep(state, cache, glam::Vec3::Y, step_height);
         let step_up = match up_hit {
             Some(hit) => (hit.time - SKIN_THICKNESS).max(0.0),
-            None => STEP_HEIGHT,
+            None => step_height,
         };
 
         // Raise position and re-run slide move with original velocity
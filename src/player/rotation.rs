@@ -7,13 +7,17 @@ use crate::window::WindowContext;
 const PITCH_LIMIT: f32 = 1.53589;
 const BASE_MOUSE_SENSITIVITY: f32 = 0.002;
 
+// Keeps the camera short of looking straight up or down.
+pub fn player_pitch_clamp(pitch: f32) -> f32 {
+    return pitch.clamp(-PITCH_LIMIT, PITCH_LIMIT);
+}
+
 pub fn player_rotation_update(
     state: &mut PlayerState,
     window: &WindowContext<'_>,
     config: &Config,
 ) {
     let sensitivity = config.mouse_sensitivity * BASE_MOUSE_SENSITIVITY;
-    state.rotation.x =
-        (state.rotation.x - window.mouse_delta().y * sensitivity).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    state.rotation.x = player_pitch_clamp(state.rotation.x - window.mouse_delta().y * sensitivity);
     state.rotation.y = (state.rotation.y - window.mouse_delta().x * sensitivity).rem_euclid(TAU);
 }
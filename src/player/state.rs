@@ -1,7 +1,12 @@
+use std::sync::Weak;
+
 use glam::{Vec2, Vec3};
 use parry3d::shape::Cuboid;
 use url::Url;
 
+use super::physics::PlayerPhysics;
+use crate::level::Level;
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum PlayerMovementMode {
     Grounded,
@@ -16,6 +21,9 @@ pub struct PlayerState {
     pub velocity: Vec3,
     pub collider: Cuboid,
     pub level_url: Option<Url>,
+    pub physics: PlayerPhysics,
+    // The level `physics` was taken from.
+    pub physics_level: Weak<Level>,
     pub last_portal: Option<(Url, String)>,
     pub open_factor: f32,
     pub wish_direction: Vec3,
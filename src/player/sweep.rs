@@ -53,6 +53,13 @@ pub fn player_sweep(
             continue;
         };
 
+        // Spawn links let the player through once the destination has loaded
+        if let Some((spawn_url, _)) = src_portal.spawn_link() {
+            if let LevelCacheResult::Ready(_) = cache.get(&spawn_url) {
+                continue;
+            }
+        }
+
         // Get linked level and destination portal
         let Some(link) = src_portal.link(cache) else {
             // Destination level not loaded or incompatible - treat as solid
@@ -1,39 +1,66 @@
+use glam::Vec3;
 use parry3d::math::{Isometry, Vector};
 
 use crate::level::cache::{LevelCache, LevelCacheResult};
+use crate::level::portal::LevelPortal;
 
 use super::constant::EPSILON;
+use super::rotation::player_pitch_clamp;
 use super::state::PlayerState;
 
+// Whether the player crossed the portal plane this step while touching the portal.
+fn player_portal_crossed(state: &PlayerState, src_portal: &LevelPortal) -> bool {
+    let start_pos = state.prev_position;
+    let src_geometry = src_portal.geometry();
+    let src_normal = src_geometry.normal();
+    let start_side = (start_pos - src_geometry.center()).dot(src_normal);
+    let end_side = (state.position - src_geometry.center()).dot(src_normal);
+    let crossed = start_side * end_side <= 0.0 && (start_side - end_side).abs() > EPSILON;
+
+    if !crossed {
+        return false;
+    }
+
+    let move_delta = state.position - start_pos;
+    let shape_pos = Isometry::translation(start_pos.x, start_pos.y, start_pos.z);
+    let shape_vel = Vector::new(move_delta.x, move_delta.y, move_delta.z);
+    return src_portal
+        .sweep(&shape_pos, &shape_vel, &state.collider, 1.0)
+        .is_some();
+}
+
 pub fn player_try_teleport(state: &mut PlayerState, cache: &mut LevelCache) -> bool {
     let level_url = state.level_url.as_ref().unwrap();
     let LevelCacheResult::Ready(level) = cache.get(level_url) else {
         return false;
     };
-    let start_pos = state.prev_position;
     for (name, src_portal) in level.portals() {
-        let Some(link) = src_portal.link(cache) else {
-            continue;
-        };
+        if let Some((spawn_url, spawn_name)) = src_portal.spawn_link() {
+            let LevelCacheResult::Ready(dst_level) = cache.get(&spawn_url) else {
+                continue;
+            };
+            if !player_portal_crossed(state, src_portal) {
+                continue;
+            }
 
-        let src_geometry = src_portal.geometry();
-        let src_normal = src_geometry.normal();
-        let start_side = (start_pos - src_geometry.center()).dot(src_normal);
-        let end_side = (state.position - src_geometry.center()).dot(src_normal);
-        let crossed = start_side * end_side <= 0.0 && (start_side - end_side).abs() > EPSILON;
+            let spawn = dst_level.spawn(Some(&spawn_name));
+            state.last_portal = None;
+            state.open_factor = 0.0;
+            state.prev_position = spawn.position();
+            state.position = spawn.position();
+            state.velocity = Vec3::ZERO;
+            state.rotation = spawn.rotation();
+            state.rotation.x = player_pitch_clamp(state.rotation.x);
+            state.level_url = Some(spawn_url);
 
-        if !crossed {
-            continue;
+            return true;
         }
 
-        let move_delta = state.position - start_pos;
-        let shape_pos = Isometry::translation(start_pos.x, start_pos.y, start_pos.z);
-        let shape_vel = Vector::new(move_delta.x, move_delta.y, move_delta.z);
-        let in_contact = src_portal
-            .sweep(&shape_pos, &shape_vel, &state.collider, 1.0)
-            .is_some();
+        let Some(link) = src_portal.link(cache) else {
+            continue;
+        };
 
-        if in_contact {
+        if player_portal_crossed(state, src_portal) {
             let yaw_delta = link.yaw_delta();
             state.last_portal = Some((level.url().clone(), name.clone()));
             state.open_factor = -state.open_factor;
@@ -0,0 +1,152 @@
+// A minimal HTTP server for tests that fetch levels over the network.
+
+// Each test crate includes this file and uses only some of its options.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use url::Url;
+
+// Runs on each request once its headers are read, given its path relative to the root.
+// Returns whether it wrote the response itself; otherwise the routes answer.
+pub type StandInRespond = Box<dyn Fn(&str, &mut TcpStream) -> bool + Send + Sync>;
+
+#[derive(Default)]
+pub struct StandInOptions {
+    // Sent with every routed response. Requests that carry it in `If-None-Match` are
+    // answered with a 304.
+    pub etag: Option<&'static str>,
+    // Connections to accept before the server closes its socket. Unlimited when `None`.
+    pub connections: Option<usize>,
+    // Answer each connection on its own thread rather than one after another.
+    pub threaded: bool,
+    pub respond: Option<StandInRespond>,
+}
+
+pub struct StandIn {
+    // The server root, which every route is relative to.
+    pub url: Url,
+    // Paths requested so far, relative to `url`.
+    pub requests: Arc<Mutex<Vec<String>>>,
+    // Header lines of each request, in the same order as `requests`.
+    pub headers: Arc<Mutex<Vec<String>>>,
+    // Finishes once `connections` have been answered.
+    pub handle: JoinHandle<()>,
+}
+
+struct StandInServer {
+    routes: HashMap<String, Vec<u8>>,
+    etag: Option<&'static str>,
+    respond: Option<StandInRespond>,
+    requests: Arc<Mutex<Vec<String>>>,
+    headers: Arc<Mutex<Vec<String>>>,
+}
+
+impl StandInServer {
+    fn answer(&self, mut stream: TcpStream) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            headers.push_str(&line);
+        }
+
+        let path = request_line.split(' ').nth(1).unwrap_or_default();
+        let path = path.strip_prefix('/').unwrap_or(path).to_string();
+        let revalidated = self.etag.is_some_and(|etag| {
+            return headers
+                .to_ascii_lowercase()
+                .contains(&format!("if-none-match: {}", etag.to_ascii_lowercase()));
+        });
+        {
+            let mut requests = self.requests.lock().unwrap();
+            let mut all_headers = self.headers.lock().unwrap();
+            requests.push(path.clone());
+            all_headers.push(headers);
+        }
+
+        if let Some(respond) = &self.respond {
+            if respond(&path, &mut stream) {
+                return;
+            }
+        }
+        let etag = match self.etag {
+            Some(etag) => format!("ETag: {etag}\r\n"),
+            None => String::new(),
+        };
+        // The client may have given up on the request, so write errors are ignored.
+        let _ = match self.routes.get(&path) {
+            Some(_) if revalidated => write!(
+                stream,
+                "HTTP/1.1 304 Not Modified\r\n{etag}Connection: close\r\n\r\n"
+            ),
+            Some(body) => write!(
+                stream,
+                "HTTP/1.1 200 OK\r\n{etag}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .and_then(|_| stream.write_all(body)),
+            None => write!(
+                stream,
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            ),
+        };
+    }
+}
+
+// Serves `routes`, keyed by path relative to the root, until the test process exits.
+// Anything else is answered with a 404.
+pub fn stand_in_serve<K: Into<String>>(routes: impl IntoIterator<Item = (K, Vec<u8>)>) -> StandIn {
+    return stand_in_serve_with(routes, StandInOptions::default());
+}
+
+pub fn stand_in_serve_with<K: Into<String>>(
+    routes: impl IntoIterator<Item = (K, Vec<u8>)>,
+    options: StandInOptions,
+) -> StandIn {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let headers = Arc::new(Mutex::new(Vec::new()));
+    let server = Arc::new(StandInServer {
+        routes: routes
+            .into_iter()
+            .map(|(path, body)| (path.into(), body))
+            .collect(),
+        etag: options.etag,
+        respond: options.respond,
+        requests: Arc::clone(&requests),
+        headers: Arc::clone(&headers),
+    });
+
+    let threaded = options.threaded;
+    let connections = options.connections.unwrap_or(usize::MAX);
+    let handle = thread::spawn(move || {
+        for stream in listener.incoming().take(connections) {
+            let stream = stream.unwrap();
+            if threaded {
+                let server = Arc::clone(&server);
+                thread::spawn(move || server.answer(stream));
+            } else {
+                server.answer(stream);
+            }
+        }
+    });
+
+    return StandIn {
+        url,
+        requests,
+        headers,
+        handle,
+    };
+}